@@ -64,7 +64,7 @@ impl Client {
 
     pub async fn get_messages(&mut self) -> VecDeque<IncomingGameMessage> {
         //std::mem::take(&mut *self.messages.write().await)
-        return VecDeque::new();
+        VecDeque::new()
     }
 
     pub fn send_message(&self, message: &OutgoingLobbyMessage) -> Result<(), Box<dyn Error>> {
@@ -102,6 +102,16 @@ impl Client {
                     Self::send(&tx, message, &client).await;
                 } else if let Ok(result) = serde_json::from_str(msg.to_str().unwrap()) {
                     let message = match result {
+                        IncomingLobbyMessage::SelectLevel(level) => LobbyMessage::SelectLevel {
+                            client: client.clone(),
+                            level,
+                        },
+                        IncomingLobbyMessage::SetControlPolicy(policy) => {
+                            LobbyMessage::SetControlPolicy(client.clone(), policy)
+                        }
+                        IncomingLobbyMessage::SetEconomyMode(mode) => {
+                            LobbyMessage::SetEconomyMode(client.clone(), mode)
+                        }
                         IncomingLobbyMessage::Start => LobbyMessage::Start(client.clone()),
                         IncomingLobbyMessage::Ping(n) => LobbyMessage::Ping(client.clone(), n),
                         IncomingLobbyMessage::Chat(message) => LobbyMessage::Chat {
@@ -144,6 +154,10 @@ impl Client {
         self.coins -= amount;
     }
 
+    pub fn set_coins(&mut self, amount: usize) {
+        self.coins = amount;
+    }
+
     async fn send(tx: &Sender<LobbyMessage>, message: LobbyMessage, client: &str) {
         if let Err(e) = tx.send(message).await {
             error!(
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+/// How the coins earned in a game are shared between the players of a lobby. Chosen by the host
+/// before the game starts.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum EconomyMode {
+    /// All players spend from and earn into one team wallet.
+    Shared,
+    /// Every player has their own wallet and the coins earned are split evenly between them.
+    #[default]
+    Split,
+    /// Every player has their own wallet and the coins of a kill go to the owner of the tower
+    /// that dealt the killing blow. Kills without a known owner are split evenly.
+    KillAttribution,
+}
@@ -1,20 +1,22 @@
 use crate::game::game_server::GameServer;
 use crate::game::players::Players;
 use crate::game::server_message::{LobbyMessage, OutgoingLobbyMessage};
-use crate::game::{Client, IncomingGameMessage, OutgoingGameMessage};
+use crate::game::{Client, EconomyMode, IncomingGameMessage, OutgoingGameMessage};
 use crate::{GamesDb, SavedGamesDb};
-use futures::future::err;
 use log::{debug, error, info, warn};
 use rand::distributions::Alphanumeric;
 use rand::Rng;
 use serde::Serialize;
+use std::collections::HashMap;
 use std::error::Error;
 use std::sync::Arc;
 use tokio::sync::mpsc::{Receiver, Sender};
 use tokio::sync::{mpsc, Mutex};
 use tokio::task::JoinHandle;
-use tower_defense::map::levels::MAP_LEVEL_1;
-use tower_defense::GameLoad;
+use tower_defense::entity::ControlPolicy;
+use tower_defense::map::levels::{get_default_level, LEVEL_REGISTRY};
+use tower_defense::map::EconomyRules;
+use tower_defense::{Income, IncomeReason};
 use warp::ws::WebSocket;
 
 const KEY_LENGTH: usize = 8;
@@ -28,6 +30,15 @@ pub struct ChatMessage {
 pub struct GameLobby {
     server: Option<Arc<Mutex<GameServer>>>,
     players: Players,
+    /// Id of the map the next game is started on.
+    level: String,
+    /// Who may upgrade and sell structures in the next game.
+    control: ControlPolicy,
+    economy: EconomyMode,
+    /// Coins left over when splitting the earnings evenly, added to the next split.
+    coin_remainder: usize,
+    /// The economy rules of the running game, which decide the interest.
+    rules: EconomyRules,
     messages: Vec<ChatMessage>,
     id: String,
     tx: Sender<LobbyMessage>,
@@ -49,6 +60,11 @@ impl GameLobby {
         Self {
             server: None,
             players,
+            level: String::from(get_default_level()),
+            control: ControlPolicy::default(),
+            economy: EconomyMode::default(),
+            coin_remainder: 0,
+            rules: EconomyRules::default(),
             messages: vec![],
             id,
             tx,
@@ -63,21 +79,39 @@ impl GameLobby {
     }
 
     pub fn join(&mut self, ws: WebSocket) {
-        let client = Client::new_client(ws, self.tx.clone());
+        let mut client = Client::new_client(ws, self.tx.clone());
+        if self.economy == EconomyMode::Shared {
+            client.set_coins(self.players.get_host().get_coins());
+        }
         self.players.add_client(client);
         self.broadcast_players();
+        self.broadcast_level();
+        self.broadcast_control();
+        self.broadcast_economy();
     }
 
     async fn start(games: GamesDb, id: String, mut rx: Receiver<LobbyMessage>) {
         if let Some(game) = games.lock().await.get(&id) {
             game.broadcast_players();
-            game.broadcast_message(&OutgoingLobbyMessage::Lobby(String::from(&game.id)), None)
+            game.broadcast_message(&OutgoingLobbyMessage::Lobby(String::from(&game.id)), None);
+            game.broadcast_level();
+            game.broadcast_control();
+            game.broadcast_economy();
         }
 
         debug!("Listening for messages");
         while let Some(result) = rx.recv().await {
             debug!("Received message: {}", &result);
             match result {
+                LobbyMessage::SelectLevel { client, level } => {
+                    Self::select_level(&games, &id, client, level).await
+                }
+                LobbyMessage::SetControlPolicy(client, policy) => {
+                    Self::set_control_policy(&games, &id, client, policy).await
+                }
+                LobbyMessage::SetEconomyMode(client, mode) => {
+                    Self::set_economy_mode(&games, &id, client, mode).await
+                }
                 LobbyMessage::Start(name) => Self::start_game(&games, &id, name).await,
                 LobbyMessage::Load { client, lobby_id } => {
                     Self::load_game(&games, &id, client, lobby_id).await
@@ -98,8 +132,8 @@ impl GameLobby {
     async fn handle_game_events(games: GamesDb, id: String, mut rx: Receiver<OutgoingGameMessage>) {
         while let Some(result) = rx.recv().await {
             if let Some(game) = games.lock().await.get_mut(&id) {
-                if let OutgoingGameMessage::CoinsReceived(coins) = result {
-                    game.receive_coins(coins);
+                if let OutgoingGameMessage::Income(income) = result {
+                    game.receive_income(income);
                 } else {
                     game.broadcast_message(&OutgoingLobbyMessage::Update(result), None);
                 }
@@ -109,14 +143,68 @@ impl GameLobby {
         }
     }
 
+    async fn select_level(games: &GamesDb, id: &str, name: String, level: String) {
+        if let Some(lobby) = games.lock().await.get_mut(id) {
+            if lobby.players.get_host().get_name() != name {
+                info!("Only the host can select the level");
+            } else if lobby.server.is_some() {
+                info!("The level cannot be changed once the game has started");
+            } else if !LEVEL_REGISTRY.contains_key(&level) {
+                warn!("Unknown level {}", level);
+            } else {
+                lobby.level = level;
+                lobby.broadcast_level();
+            }
+        }
+    }
+
+    async fn set_control_policy(games: &GamesDb, id: &str, name: String, policy: ControlPolicy) {
+        if let Some(lobby) = games.lock().await.get_mut(id) {
+            if lobby.players.get_host().get_name() != name {
+                info!("Only the host can change the control policy");
+            } else if lobby.server.is_some() {
+                info!("The control policy cannot be changed once the game has started");
+            } else {
+                lobby.control = policy;
+                lobby.broadcast_control();
+            }
+        }
+    }
+
+    async fn set_economy_mode(games: &GamesDb, id: &str, name: String, mode: EconomyMode) {
+        if let Some(lobby) = games.lock().await.get_mut(id) {
+            if lobby.players.get_host().get_name() != name {
+                info!("Only the host can change the economy mode");
+            } else if lobby.server.is_some() {
+                info!("The economy mode cannot be changed once the game has started");
+            } else {
+                lobby.economy = mode;
+                if mode == EconomyMode::Shared {
+                    let coins = lobby.players.get_host().get_coins();
+                    for player in lobby.players.iter_mut() {
+                        player.set_coins(coins);
+                    }
+                }
+                lobby.broadcast_economy();
+            }
+        }
+    }
+
     async fn start_game(games: &GamesDb, id: &str, name: String) {
         if let Some(lobby) = games.lock().await.get_mut(id) {
             if lobby.players.get_host().get_name() != name {
                 info!("Only the host can start the game");
-                return;
             } else {
                 let (tx, rx) = mpsc::channel(32);
-                let game_server = GameServer::new(&MAP_LEVEL_1, tx);
+                let seed = rand::thread_rng().gen();
+                info!(
+                    "Starting game {} on level {} with seed {}",
+                    id, lobby.level, seed
+                );
+                let map = LEVEL_REGISTRY[&lobby.level].clone();
+                let game_server = GameServer::new(map, seed, lobby.control, lobby.economy, tx);
+                lobby.coin_remainder = 0;
+                lobby.rules = game_server.get_economy_rules().clone();
                 let game_server = Arc::new(Mutex::new(game_server));
                 let handle = tokio::spawn(GameLobby::handle_game_events(
                     games.clone(),
@@ -134,22 +222,39 @@ impl GameLobby {
         if let Some(lobby) = games.lock().await.get_mut(id) {
             if lobby.players.get_host().get_name() != name {
                 info!("Only the host can start the game");
-                return;
             } else {
-                if let Some(saved_game) = lobby.saved_games.lock().await.get(&lobby_id) {
-                    let (tx, rx) = mpsc::channel(32);
-                    let game_server = GameServer::load(&MAP_LEVEL_1, tx, saved_game);
-                    let game_server = Arc::new(Mutex::new(game_server));
-                    let handle = tokio::spawn(GameLobby::handle_game_events(
-                        games.clone(),
-                        String::from(id),
-                        rx,
-                    ));
-                    lobby.game_handle = Some(handle);
-                    GameServer::start(game_server.clone());
-                    lobby.server = Some(game_server);
-                } else {
-                    info!("Lobby not found");
+                let (tx, rx) = mpsc::channel(32);
+                let loaded = match lobby.saved_games.lock().await.get(&lobby_id) {
+                    Some(saved_game) => GameServer::load(tx, saved_game),
+                    None => Err(format!("There is no saved game {}", lobby_id)),
+                };
+                match loaded {
+                    Ok(game_server) => {
+                        lobby.level = String::from(game_server.get_map_id());
+                        lobby.control = game_server.get_control();
+                        lobby.economy = game_server.get_economy();
+                        lobby.coin_remainder = 0;
+                        lobby.rules = game_server.get_economy_rules().clone();
+                        lobby.broadcast_level();
+                        lobby.broadcast_control();
+                        lobby.broadcast_economy();
+                        let game_server = Arc::new(Mutex::new(game_server));
+                        let handle = tokio::spawn(GameLobby::handle_game_events(
+                            games.clone(),
+                            String::from(id),
+                            rx,
+                        ));
+                        lobby.game_handle = Some(handle);
+                        GameServer::start(game_server.clone());
+                        lobby.server = Some(game_server);
+                    }
+                    Err(reason) => {
+                        warn!("Could not load game {}: {}", lobby_id, reason);
+                        let message = OutgoingLobbyMessage::LoadFailed(reason);
+                        if let Err(e) = lobby.players.get_host().send_message(&message) {
+                            error!("Could not send load failure: {}", e);
+                        }
+                    }
                 }
             }
         }
@@ -200,6 +305,14 @@ impl GameLobby {
                         id
                     ),
                 }
+                // Every wallet mirrors the team wallet, so spending or refunds of one player
+                // apply to all of them.
+                let coins = player.get_coins();
+                if lobby.economy == EconomyMode::Shared {
+                    for player in lobby.players.iter_mut() {
+                        player.set_coins(coins);
+                    }
+                }
             }
         } else {
             error!("Lobby {} not found", id);
@@ -211,9 +324,8 @@ impl GameLobby {
             Some(game) => {
                 if name == game.players.get_host().get_name() {
                     // Close game and remove from lobby from games list
-                    match &mut game.server {
-                        Some(server) => server.lock().await.close_game(),
-                        None => (),
+                    if let Some(server) = &mut game.server {
+                        server.lock().await.close_game();
                     }
                     game.broadcast_message(
                         &OutgoingLobbyMessage::GameClosed,
@@ -221,6 +333,10 @@ impl GameLobby {
                     );
                     true
                 } else {
+                    if let Some(server) = &game.server {
+                        let host = game.players.get_host().get_name();
+                        server.lock().await.transfer_structures(&name, host);
+                    }
                     game.players.remove_client(&name);
                     game.broadcast_players();
                     false
@@ -244,7 +360,7 @@ impl GameLobby {
     async fn handle_save(games: &GamesDb, id: &str) {
         if let Some(lobby) = games.lock().await.get_mut(id) {
             if let Some(server) = &lobby.server {
-                match serde_json::to_string(&*server.lock().await) {
+                match serde_json::to_string(&server.lock().await.save()) {
                     Ok(game) => {
                         let id = loop {
                             let id = generate_lobby_key();
@@ -271,6 +387,18 @@ impl GameLobby {
         self.broadcast_message(&message, None);
     }
 
+    fn broadcast_level(&self) {
+        self.broadcast_message(&OutgoingLobbyMessage::Level(self.level.clone()), None);
+    }
+
+    fn broadcast_control(&self) {
+        self.broadcast_message(&OutgoingLobbyMessage::ControlPolicy(self.control), None);
+    }
+
+    fn broadcast_economy(&self) {
+        self.broadcast_message(&OutgoingLobbyMessage::EconomyMode(self.economy), None);
+    }
+
     fn broadcast_message(&self, message: &OutgoingLobbyMessage, predicate: Option<&str>) {
         if let Err(e) = self.broadcast_message_err(message, predicate) {
             error!("{}", e);
@@ -302,20 +430,72 @@ impl GameLobby {
         Ok(())
     }
 
-    fn receive_coins(&mut self, amount: usize) {
-        for player in self.players.iter_mut() {
-            player.receive_coins(amount);
+    /// Pays out `income` according to the economy mode and tells every player what they
+    /// received. When a wave is cleared every wallet also earns interest on its coins.
+    fn receive_income(&mut self, income: Vec<Income>) {
+        let rules = &self.rules;
+        let mut received: HashMap<String, Vec<Income>> = HashMap::new();
+        let mut pay = |player: &mut Client, income: Income| {
+            player.receive_coins(income.get_amount());
+            if income.get_amount() > 0 {
+                let name = String::from(player.get_name());
+                received.entry(name).or_default().push(income);
+            }
+        };
+
+        for income in income {
+            if let IncomeReason::WaveCleared { wave } = *income.get_reason() {
+                for player in self.players.iter_mut() {
+                    let interest = rules.interest(player.get_coins());
+                    pay(
+                        player,
+                        Income::new(interest, IncomeReason::Interest { wave }),
+                    );
+                }
+            }
+
+            let owner = match (self.economy, income.get_reason()) {
+                (EconomyMode::KillAttribution, IncomeReason::Kill(kill)) => kill.get_owner(),
+                _ => None,
+            };
+            if let Some(player) = owner.and_then(|owner| self.players.find_client_mut(owner)) {
+                pay(player, income);
+                continue;
+            }
+
+            // Every wallet mirrors the team wallet in the shared mode, otherwise the coins are
+            // split evenly.
+            let amount = match self.economy {
+                EconomyMode::Shared => income.get_amount(),
+                _ => {
+                    let amount = income.get_amount() + self.coin_remainder;
+                    let count = (&self.players).into_iter().count();
+                    self.coin_remainder = amount % count;
+                    amount / count
+                }
+            };
+            for player in self.players.iter_mut() {
+                pay(player, Income::new(amount, income.get_reason().clone()));
+            }
+        }
+
+        for player in &self.players {
+            if let Some(income) = received.remove(player.get_name()) {
+                let message = OutgoingLobbyMessage::IncomeReceived(income);
+                if let Err(e) = player.send_message(&message) {
+                    error!("Could not send income: {}", e);
+                }
+            }
         }
     }
 }
 
-impl<'a> Drop for GameLobby {
+impl Drop for GameLobby {
     fn drop(&mut self) {
         debug!("Waiting for lobby {} to finish", self.id);
         self.handle.abort();
-        match &self.game_handle {
-            Some(handle) => handle.abort(),
-            None => (),
+        if let Some(handle) = &self.game_handle {
+            handle.abort();
         }
         debug!("Aborting lobby listener");
     }
@@ -1,4 +1,5 @@
-use crate::game::{Client, IncomingGameMessage, OutgoingGameMessage};
+use crate::game::server_message::OutgoingLobbyMessage;
+use crate::game::{Client, EconomyMode, IncomingGameMessage, OutgoingGameMessage};
 use futures::{stream, StreamExt};
 use log::{debug, error, trace};
 use serde::Serialize;
@@ -7,74 +8,95 @@ use std::time::Instant;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::Mutex;
 use tokio::time::{self, Duration, Interval};
-use tower_defense::entity::{
-    GameStructure, KonfettiKanoneV1, KonfettiKanoneV2, LightningTower, LightningTowerV1,
-    SingleShotTowerV1,
-};
-use tower_defense::map::Map;
-use tower_defense::{Game, GameLoad};
-
-const TICK_RATE: u64 = 30;
+use tower_defense::entity::{ControlPolicy, GameStructure, StructureType};
+use tower_defense::map::levels::LEVEL_REGISTRY;
+use tower_defense::map::{EconomyRules, Map};
+use tower_defense::tick;
+use tower_defense::{Game, GameLoad, GameSave};
 
 #[derive(Debug, Clone)]
 struct GameError;
 
-#[derive(Debug, Clone)]
-struct GameClosed;
-
+/// A running game as it is written to a save, read back by [`GameServer::load`].
 #[derive(Serialize)]
-pub struct GameServer {
+pub struct GameServerSave<'a> {
     #[serde(flatten)]
-    game: Game,
+    game: GameSave<'a>,
 
-    #[serde(skip_serializing)]
-    interval: Interval,
+    /// How the lobby shares the coins of this game. Saved with the game so that a loaded game
+    /// continues with the same mode.
+    economy: EconomyMode,
+}
 
-    #[serde(skip_serializing)]
+pub struct GameServer {
+    game: Game,
+    /// How the lobby shares the coins of this game.
+    economy: EconomyMode,
+    interval: Interval,
     last_instant: Instant,
-
-    #[serde(skip_serializing)]
     closed: bool,
-
-    #[serde(skip_serializing)]
     tx: Sender<OutgoingGameMessage>,
 }
 
 impl GameServer {
-    pub fn new(map: &'static Map, tx: Sender<OutgoingGameMessage>) -> Self {
+    pub fn new(
+        map: Arc<Map>,
+        seed: u64,
+        control: ControlPolicy,
+        economy: EconomyMode,
+        tx: Sender<OutgoingGameMessage>,
+    ) -> Self {
         Self {
-            game: Game::new(map),
-            interval: time::interval(Duration::from_millis(1000 / TICK_RATE)),
+            game: Game::new(map, seed).with_control(control),
+            economy,
+            interval: time::interval(Duration::from_millis(tick::TICK_DURATION)),
             last_instant: Instant::now(),
             closed: false,
             tx,
         }
     }
 
-    pub fn load(map: &'static Map, tx: Sender<OutgoingGameMessage>, data: &str) -> Self {
-        let game: serde_json::Value = serde_json::from_str(data).unwrap();
-        let game_load: GameLoad = serde_json::from_value(game.clone()).unwrap();
+    /// Restores a saved game. Fails if the save cannot be read or refers to a map or structure
+    /// that does not exist (anymore).
+    pub fn load(tx: Sender<OutgoingGameMessage>, data: &str) -> Result<Self, String> {
+        let game: serde_json::Value = serde_json::from_str(data).map_err(|e| e.to_string())?;
+        let game_load: GameLoad =
+            serde_json::from_value(game.clone()).map_err(|e| e.to_string())?;
+        let map = LEVEL_REGISTRY
+            .get(game_load.get_map())
+            .ok_or_else(|| format!("Unknown map: {}", game_load.get_map()))?
+            .clone();
         let mut structures: Vec<Box<dyn GameStructure>> = vec![];
-        for structure in game["structures"].as_array().unwrap() {
-            let model = structure["model"].as_str().unwrap();
-            match model {
-                "LightningTower" => structures.push(Box::new(LightningTower::load(structure))),
-                "LightningTowerV1" => structures.push(Box::new(LightningTowerV1::load(structure))),
-                "KonfettiKanoneV1" => structures.push(Box::new(KonfettiKanoneV1::load(structure))),
-                "KonfettiKanoneV2" => structures.push(Box::new(KonfettiKanoneV2::load(structure))),
-                "SingleShotTowerV1" => {
-                    structures.push(Box::new(SingleShotTowerV1::load(structure)))
-                }
-                _ => panic!("Unknown model: {}", model),
-            };
+        let saved_structures = game["structures"]
+            .as_array()
+            .ok_or("The save contains no structures")?;
+        for structure in saved_structures {
+            let model = structure["model"]
+                .as_str()
+                .ok_or("A saved structure has no model")?;
+            let structure_type = StructureType::try_from(String::from(model))?;
+            structures.push(structure_type.load(structure).map_err(|e| e.to_string())?);
         }
+        // Saves from before the economy mode was saved continue with the default mode.
+        let economy = match game.get("economy") {
+            Some(economy) => serde_json::from_value(economy.clone()).map_err(|e| e.to_string())?,
+            None => EconomyMode::default(),
+        };
 
-        Self {
+        Ok(Self {
             game: Game::load(map, game_load, structures),
-            interval: time::interval(Duration::from_millis(1000 / TICK_RATE)),
+            economy,
+            interval: time::interval(Duration::from_millis(tick::TICK_DURATION)),
             last_instant: Instant::now(),
             closed: false,
             tx,
+        })
+    }
+
+    pub fn save(&self) -> GameServerSave<'_> {
+        GameServerSave {
+            game: self.game.save(),
+            economy: self.economy,
         }
     }
 
@@ -96,10 +118,10 @@ impl GameServer {
         }
         stream::unfold(this, |state| async {
             let result = state.lock().await.tick().await;
-            return match result {
+            match result {
                 Ok(()) => Some(((), state)),
                 Err(_) => None,
-            };
+            }
         })
         .for_each(|_| async {})
         .await;
@@ -113,12 +135,19 @@ impl GameServer {
 
         self.interval.tick().await;
 
-        let now = Instant::now();
-        let delta_time = now - self.last_instant;
-        let gold_earned = self.game.update(delta_time.as_micros() as f64 / 1_000.0);
-
-        self.broadcast_message(OutgoingGameMessage::CoinsReceived(gold_earned))
-            .await;
+        // Only whole milliseconds are handed to the game, the remainder stays in `last_instant`.
+        let elapsed = (Instant::now() - self.last_instant).as_millis() as u64;
+        self.last_instant += Duration::from_millis(elapsed);
+        let income = self.game.update(elapsed);
+        if !income.is_empty() {
+            self.broadcast_message(OutgoingGameMessage::Income(income))
+                .await;
+        }
+        let events = self.game.take_events();
+        if !events.is_empty() {
+            self.broadcast_message(OutgoingGameMessage::Events(events))
+                .await;
+        }
 
         trace!("Sending message");
         match serde_json::to_string(&self.game) {
@@ -130,7 +159,6 @@ impl GameServer {
                 error!("Could not convert game to json: {}", e);
             }
         };
-        self.last_instant = now;
 
         Ok(())
     }
@@ -143,27 +171,66 @@ impl GameServer {
                 if cost > coin {
                     return;
                 }
-                if let Ok(_) = self.game.try_place_structure(structure, pos) {
-                    client.remove_coins(cost);
-                }
-            }
-            IncomingGameMessage::UpgradeStructure { id } => {
-                if let Some(structure) = self.game.find_structure(id) {
-                    if let Some(upgrade) = structure.get_upgrade() {
-                        let cost = upgrade.get_model().get_cost();
-                        let coins = client.get_coins();
-                        if cost > coins {
-                            return;
-                        }
-                        if let Ok(_) = self.game.upgrade_structure(id) {
-                            client.remove_coins(cost);
+                match self
+                    .game
+                    .try_place_structure(structure, pos, client.get_name())
+                {
+                    Ok(()) => client.remove_coins(cost),
+                    Err(reason) => {
+                        let message = OutgoingLobbyMessage::PlacementRejected(reason);
+                        if let Err(e) = client.send_message(&message) {
+                            error!("Could not send placement rejection: {}", e);
                         }
                     }
                 }
             }
+            IncomingGameMessage::UpgradeStructure { id, target } => {
+                let cost = target.get_model().get_cost();
+                if cost > client.get_coins() {
+                    return;
+                }
+                match self.game.upgrade_structure(id, target, client.get_name()) {
+                    Ok(()) => client.remove_coins(cost),
+                    Err(e) => debug!("Could not upgrade structure: {}", e.message()),
+                }
+            }
+            IncomingGameMessage::SellStructure { id } => {
+                match self.game.sell_structure(id, client.get_name()) {
+                    Ok(refund) => client.receive_coins(refund),
+                    Err(e) => debug!("Could not sell structure: {}", e.message()),
+                }
+            }
+            IncomingGameMessage::SetTargeting { id, mode } => {
+                if let Err(e) = self.game.set_targeting(id, mode) {
+                    debug!("Could not set targeting: {}", e.message());
+                }
+            }
         }
     }
 
+    pub fn get_map_id(&self) -> &str {
+        self.game.get_map().get_id()
+    }
+
+    pub fn get_control(&self) -> ControlPolicy {
+        self.game.get_control()
+    }
+
+    pub fn get_economy(&self) -> EconomyMode {
+        self.economy
+    }
+
+    /// The economy rules of the map the game is played on.
+    pub fn get_economy_rules(&self) -> &EconomyRules {
+        self.game.get_map().get_economy()
+    }
+
+    /// Hands the structures of a player who left over to `to`.
+    pub fn transfer_structures(&mut self, from: &str, to: &str) {
+        let count = self.game.transfer_structures(from, to);
+        debug!("Handed {} structures of {} over to {}", count, from, to);
+    }
+
     pub fn close_game(&mut self) {
         self.closed = true;
     }
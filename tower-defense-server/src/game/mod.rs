@@ -1,10 +1,11 @@
 mod client;
+mod economy;
 mod game_lobby;
 mod game_server;
 mod players;
 mod server_message;
 
-pub use client::{Client, ClientReceiver, ClientSender};
+pub use client::Client;
+pub use economy::EconomyMode;
 pub use game_lobby::GameLobby;
-pub use game_server::GameServer;
 pub use server_message::{IncomingGameMessage, OutgoingGameMessage};
@@ -6,11 +6,6 @@ pub struct Players {
     clients: Vec<Client>,
 }
 
-trait IntoMutIterator {
-    type Item;
-    type IntoIter;
-}
-
 impl Players {
     pub fn new(host: Client) -> Self {
         assert!(host.is_host());
@@ -105,13 +100,7 @@ impl<'a> Iterator for PlayersMutIterator<'a> {
             let ptr = &mut self.players.host as *mut Client;
             unsafe { Some(&mut *ptr) }
         } else if self.index <= self.players.clients.len() {
-            Some(unsafe {
-                &mut *self
-                    .players
-                    .clients
-                    .as_mut_ptr()
-                    .offset((self.index - 1) as isize)
-            })
+            Some(unsafe { &mut *self.players.clients.as_mut_ptr().add(self.index - 1) })
         } else {
             None
         };
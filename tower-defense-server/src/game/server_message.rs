@@ -1,13 +1,19 @@
 use crate::game::game_lobby::ChatMessage;
+use crate::game::EconomyMode;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::fmt::Formatter;
-use tower_defense::entity::StructureType;
+use tower_defense::entity::{ControlPolicy, StructureType, TargetingMode};
+use tower_defense::map::PlacementError;
 use tower_defense::math::Vector2;
+use tower_defense::{GameEvent, Income};
 
 #[derive(Deserialize, Debug)]
 #[serde(tag = "message", content = "data")]
 pub enum IncomingLobbyMessage {
+    SelectLevel(String),
+    SetControlPolicy(ControlPolicy),
+    SetEconomyMode(EconomyMode),
     Start,
     Load(String),
     Ping(u64),
@@ -24,6 +30,9 @@ impl fmt::Display for IncomingLobbyMessage {
 #[derive(Deserialize, Debug)]
 #[serde(tag = "message", content = "data")]
 pub enum LobbyMessage {
+    SelectLevel { client: String, level: String },
+    SetControlPolicy(String, ControlPolicy),
+    SetEconomyMode(String, EconomyMode),
     Start(String),
     Load { client: String, lobby_id: String },
     Ping(String, u64),
@@ -44,12 +53,18 @@ impl fmt::Display for LobbyMessage {
 pub enum OutgoingLobbyMessage {
     Players(Vec<String>),
     Lobby(String),
+    Level(String),
+    ControlPolicy(ControlPolicy),
+    EconomyMode(EconomyMode),
     Pong(u64),
     Chat(Vec<ChatMessage>),
     NewChatMessage(ChatMessage),
     GameClosed,
     Update(OutgoingGameMessage),
     ClientUpdate(OutgoingGameMessage, usize),
+    PlacementRejected(PlacementError),
+    LoadFailed(String),
+    IncomeReceived(Vec<Income>),
 }
 
 #[derive(Deserialize, Debug)]
@@ -61,6 +76,14 @@ pub enum IncomingGameMessage {
     },
     UpgradeStructure {
         id: usize,
+        target: StructureType,
+    },
+    SellStructure {
+        id: usize,
+    },
+    SetTargeting {
+        id: usize,
+        mode: TargetingMode,
     },
 }
 
@@ -75,5 +98,6 @@ impl fmt::Display for IncomingGameMessage {
 pub enum OutgoingGameMessage {
     Map(String),
     Update(String),
-    CoinsReceived(usize),
+    Income(Vec<Income>),
+    Events(Vec<GameEvent>),
 }
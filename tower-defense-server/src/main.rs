@@ -1,7 +1,6 @@
 extern crate core;
 
 use crate::game::GameLobby;
-use futures::TryFutureExt;
 use handler::LobbyNotFoundError;
 use log::trace;
 use std::collections::HashMap;
@@ -9,6 +8,7 @@ use std::convert::Infallible;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tower_defense::entity::{ENEMY_MODEL_MAP, STRUCTURE_MODEL_MAP};
+use tower_defense::map::levels::LEVEL_REGISTRY;
 use warp::http::StatusCode;
 use warp::{Filter, Rejection};
 
@@ -23,6 +23,11 @@ pub type SavedGamesDb = Arc<Mutex<HashMap<String, String>>>;
 pub async fn main() {
     pretty_env_logger::init();
 
+    // Load the definitions up front so invalid resource files are reported on startup.
+    lazy_static::initialize(&STRUCTURE_MODEL_MAP);
+    lazy_static::initialize(&ENEMY_MODEL_MAP);
+    lazy_static::initialize(&LEVEL_REGISTRY);
+
     let games = Arc::new(Mutex::new(HashMap::new()));
     let saved_games = Arc::new(Mutex::new(HashMap::new()));
 
@@ -57,6 +62,8 @@ pub async fn main() {
 
     let enemy_data = warp::path("enemies").map(|| warp::reply::json(&*ENEMY_MODEL_MAP));
 
+    let map_data = warp::path("maps").map(|| warp::reply::json(&*LEVEL_REGISTRY));
+
     let saved_games = warp::path("games")
         .and(with_saved_games_db(saved_games.clone()))
         .and_then(handler::get_saved_games);
@@ -67,6 +74,7 @@ pub async fn main() {
         .or(resources)
         .or(structure_data)
         .or(enemy_data)
+        .or(map_data)
         .or(saved_games)
         .with(warp::cors().allow_any_origin());
 
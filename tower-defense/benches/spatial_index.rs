@@ -0,0 +1,88 @@
+//! Compares the spatial index against scanning every enemy, which is what towers and projectiles
+//! did before. Run with `cargo bench -p tower-defense`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use tower_defense::math::{SpatialIndex, Vector2};
+
+const WIDTH: f64 = 1920.0;
+const HEIGHT: f64 = 1080.0;
+const RANGE: f64 = 150.0;
+
+/// Deterministic points spread over the whole map.
+fn points(count: usize) -> Vec<Vector2> {
+    (0..count)
+        .map(|i| {
+            let x = (i * 7919 % 1920) as f64;
+            let y = (i * 104729 % 1080) as f64;
+            Vector2::new(x, y)
+        })
+        .collect()
+}
+
+/// One query per tower, spread over the map.
+fn towers() -> Vec<Vector2> {
+    (0..50)
+        .map(|i| Vector2::new((i * 389 % 1920) as f64, (i * 211 % 1080) as f64))
+        .collect()
+}
+
+fn within(c: &mut Criterion) {
+    let mut group = c.benchmark_group("within");
+    let towers = towers();
+    for count in [1000, 2500, 5000] {
+        let points = points(count);
+        group.bench_with_input(BenchmarkId::new("linear", count), &points, |b, points| {
+            b.iter(|| {
+                for tower in &towers {
+                    let found: Vec<usize> = (0..points.len())
+                        .filter(|&i| (&points[i] - tower).magnitude() < RANGE)
+                        .collect();
+                    black_box(found);
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("index", count), &points, |b, points| {
+            let mut index = SpatialIndex::new(WIDTH, HEIGHT, 100.0);
+            b.iter(|| {
+                index.rebuild(points);
+                for tower in &towers {
+                    black_box(index.within(tower, RANGE));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn nearest(c: &mut Criterion) {
+    let mut group = c.benchmark_group("nearest");
+    let towers = towers();
+    for count in [1000, 2500, 5000] {
+        let points = points(count);
+        group.bench_with_input(BenchmarkId::new("linear", count), &points, |b, points| {
+            b.iter(|| {
+                for tower in &towers {
+                    let found = points
+                        .iter()
+                        .map(|point| (point - tower).magnitude())
+                        .filter(|distance| *distance < RANGE)
+                        .min_by(|a, b| a.total_cmp(b));
+                    black_box(found);
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("index", count), &points, |b, points| {
+            let mut index = SpatialIndex::new(WIDTH, HEIGHT, 100.0);
+            b.iter(|| {
+                index.rebuild(points);
+                for tower in &towers {
+                    black_box(index.nearest(tower, RANGE, |_| true));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, within, nearest);
+criterion_main!(benches);
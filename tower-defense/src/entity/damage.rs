@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The kind of damage a tower or effect deals. Enemies can resist some kinds better than others.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum DamageType {
+    /// Reduced by the armor of the enemy.
+    #[default]
+    Physical,
+    Electric,
+    Explosive,
+    Fire,
+    Poison,
+}
+
+/// Resistance of an enemy per damage type. A resistance of 0.25 blocks a quarter of the damage,
+/// negative resistances increase it.
+pub type Resistances = BTreeMap<DamageType, f64>;
+
+/// An amount of damage of a single type, before armor and resistances are applied.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Damage {
+    amount: f64,
+    damage_type: DamageType,
+}
+
+impl Damage {
+    pub fn new(amount: f64, damage_type: DamageType) -> Self {
+        Self {
+            amount,
+            damage_type,
+        }
+    }
+
+    pub fn get_amount(&self) -> f64 {
+        self.amount
+    }
+
+    pub fn get_damage_type(&self) -> DamageType {
+        self.damage_type
+    }
+
+    /// The damage left after `armor` and `resistances`. Armor is subtracted from physical damage
+    /// before the resistance is applied. Never negative.
+    pub fn mitigate(&self, armor: f64, resistances: &Resistances) -> f64 {
+        let mut amount = self.amount;
+        if self.damage_type == DamageType::Physical {
+            amount -= armor;
+        }
+        let resistance = resistances.get(&self.damage_type).copied().unwrap_or(0.0);
+
+        (amount * (1.0 - resistance)).max(0.0)
+    }
+}
+
+/// The structure that dealt damage and the player who owned it at that time. Kills are credited
+/// to that player even if the structure has been upgraded or sold since.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DamageSource {
+    structure: usize,
+    #[serde(default)]
+    owner: Option<String>,
+}
+
+impl DamageSource {
+    pub fn new(structure: usize, owner: Option<String>) -> Self {
+        Self { structure, owner }
+    }
+
+    pub fn get_structure(&self) -> usize {
+        self.structure
+    }
+
+    pub fn get_owner(&self) -> Option<&str> {
+        self.owner.as_deref()
+    }
+}
+
+/// A structure that is not owned by any player.
+impl From<usize> for DamageSource {
+    fn from(structure: usize) -> Self {
+        Self::new(structure, None)
+    }
+}
+
+#[cfg(test)]
+mod damage_tests {
+    use crate::entity::{Damage, DamageType, Resistances};
+
+    #[test]
+    fn mitigate() {
+        let resistances =
+            Resistances::from([(DamageType::Physical, 0.5), (DamageType::Electric, -0.5)]);
+
+        let physical = Damage::new(30.0, DamageType::Physical);
+        assert_eq!(physical.mitigate(10.0, &resistances), 10.0);
+        assert_eq!(physical.mitigate(40.0, &resistances), 0.0);
+        let electric = Damage::new(30.0, DamageType::Electric);
+        assert_eq!(electric.mitigate(10.0, &resistances), 45.0);
+        let fire = Damage::new(30.0, DamageType::Fire);
+        assert_eq!(fire.mitigate(10.0, &resistances), 30.0);
+    }
+}
@@ -0,0 +1,237 @@
+use crate::entity::enemy::enemy_type::EnemyType;
+use crate::entity::Movement;
+use crate::math::Vector2;
+use crate::tick::{self, Tick};
+use serde::{Deserialize, Serialize};
+
+/// Special behaviour of an enemy, declared on its model.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type")]
+pub enum Ability {
+    /// Splits into `count` enemies of the type `into` when killed. They continue along the path
+    /// from where it died.
+    Split { into: String, count: u32 },
+    /// Heals the other living enemies within `radius` by `amount` every `cooldown` ms.
+    Heal {
+        radius: f64,
+        amount: f64,
+        cooldown: f64,
+    },
+    /// A shield of up to `max_shield` points that has to be broken before the enemy takes
+    /// damage. It regenerates `regeneration` points per second once the enemy has not been hit
+    /// for `delay` ms.
+    Shield {
+        max_shield: f64,
+        regeneration: f64,
+        delay: f64,
+    },
+    /// Regenerates `per_second` health, up to the maximum health.
+    Regeneration { per_second: f64 },
+}
+
+/// The state of the ability at the same position in the model of the enemy.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type")]
+pub enum AbilityState {
+    Split,
+    Heal { ready_in: Tick },
+    Shield { shield: f64, since_hit: Tick },
+    Regeneration,
+}
+
+impl AbilityState {
+    fn new(ability: &Ability) -> Self {
+        match ability {
+            Ability::Split { .. } => Self::Split,
+            Ability::Heal { .. } => Self::Heal { ready_in: 0 },
+            Ability::Shield { max_shield, .. } => Self::Shield {
+                shield: *max_shield,
+                since_hit: 0,
+            },
+            Ability::Regeneration { .. } => Self::Regeneration,
+        }
+    }
+}
+
+/// What the abilities and phases of an enemy do to the game. Applied by the game.
+#[derive(Clone, Debug)]
+pub enum AbilityEvent {
+    /// Heals the other living enemies within `radius` of `center`.
+    Heal {
+        source: usize,
+        center: Vector2,
+        radius: f64,
+        amount: f64,
+    },
+    /// Spawns `count` enemies of the type `into` at `pos`, `distance` along the route the
+    /// spawning enemy takes on `path` with its `movement`.
+    Spawn {
+        into: EnemyType,
+        count: u32,
+        path: String,
+        movement: Movement,
+        distance: f64,
+        pos: Vector2,
+    },
+    /// The boss `source` entered the phase with the given number, starting at 1.
+    PhaseChanged { source: usize, phase: usize },
+}
+
+/// The state of all abilities of an enemy. Part of the serialized enemy, so clients can render
+/// e.g. the shield.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(transparent)]
+pub struct Abilities {
+    states: Vec<AbilityState>,
+}
+
+impl Abilities {
+    pub fn new(abilities: &[Ability]) -> Self {
+        Self {
+            states: abilities.iter().map(AbilityState::new).collect(),
+        }
+    }
+
+    pub fn get_states(&self) -> &[AbilityState] {
+        &self.states
+    }
+
+    /// Whether the state belongs to `abilities`, i.e. there is a state of the same kind for
+    /// every ability.
+    pub fn matches(&self, abilities: &[Ability]) -> bool {
+        self.states.len() == abilities.len()
+            && self.states.iter().zip(abilities).all(|(state, ability)| {
+                matches!(
+                    (state, ability),
+                    (AbilityState::Split, Ability::Split { .. })
+                        | (AbilityState::Heal { .. }, Ability::Heal { .. })
+                        | (AbilityState::Shield { .. }, Ability::Shield { .. })
+                        | (AbilityState::Regeneration, Ability::Regeneration { .. })
+                )
+            })
+    }
+
+    /// The shield points left.
+    pub fn get_shield(&self) -> f64 {
+        self.states
+            .iter()
+            .map(|state| match state {
+                AbilityState::Shield { shield, .. } => *shield,
+                _ => 0.0,
+            })
+            .sum()
+    }
+
+    /// Lets the shields absorb as much of `amount` as they can and returns the rest.
+    pub fn absorb(&mut self, mut amount: f64) -> f64 {
+        for state in self.states.iter_mut() {
+            if let AbilityState::Shield { shield, since_hit } = state {
+                let absorbed = amount.min(*shield);
+                *shield -= absorbed;
+                *since_hit = 0;
+                amount -= absorbed;
+            }
+        }
+
+        amount
+    }
+
+    /// Advances the abilities by a tick. Returns the health regenerated during this tick and
+    /// the heals of other enemies, each with its radius and amount.
+    pub fn update(&mut self, abilities: &[Ability]) -> (f64, Vec<(f64, f64)>) {
+        let mut regenerated = 0.0;
+        let mut heals = vec![];
+        for (ability, state) in abilities.iter().zip(self.states.iter_mut()) {
+            match (ability, state) {
+                (
+                    Ability::Heal {
+                        radius,
+                        amount,
+                        cooldown,
+                    },
+                    AbilityState::Heal { ready_in },
+                ) => {
+                    if *ready_in == 0 {
+                        heals.push((*radius, *amount));
+                        *ready_in = tick::from_millis(*cooldown);
+                    } else {
+                        *ready_in -= 1;
+                    }
+                }
+                (
+                    Ability::Shield {
+                        max_shield,
+                        regeneration,
+                        delay,
+                    },
+                    AbilityState::Shield { shield, since_hit },
+                ) => {
+                    if *since_hit >= tick::from_millis(*delay) {
+                        *shield = (*shield + regeneration * tick::DELTA_TIME).min(*max_shield);
+                    } else {
+                        *since_hit += 1;
+                    }
+                }
+                (Ability::Regeneration { per_second }, AbilityState::Regeneration) => {
+                    regenerated += per_second * tick::DELTA_TIME;
+                }
+                _ => {}
+            }
+        }
+
+        (regenerated, heals)
+    }
+}
+
+#[cfg(test)]
+mod ability_tests {
+    use crate::entity::{Abilities, Ability};
+    use crate::tick;
+
+    #[test]
+    fn shield() {
+        let abilities = [Ability::Shield {
+            max_shield: 30.0,
+            regeneration: 50.0,
+            delay: 100.0,
+        }];
+        let mut state = Abilities::new(&abilities);
+        assert_eq!(state.absorb(20.0), 0.0);
+        assert_eq!(state.absorb(20.0), 10.0);
+        assert_eq!(state.get_shield(), 0.0);
+
+        // Nothing regenerates until the enemy has not been hit for the delay.
+        for _ in 0..tick::from_millis(100.0) {
+            state.update(&abilities);
+        }
+        assert_eq!(state.get_shield(), 0.0);
+        state.update(&abilities);
+        assert_eq!(state.get_shield(), 1.0);
+    }
+
+    #[test]
+    fn heal_cooldown() {
+        let abilities = [Ability::Heal {
+            radius: 100.0,
+            amount: 5.0,
+            cooldown: 100.0,
+        }];
+        let mut state = Abilities::new(&abilities);
+        let heals: usize = (0..12).map(|_| state.update(&abilities).1.len()).sum();
+        assert_eq!(heals, 2);
+    }
+
+    #[test]
+    fn matches() {
+        let shield = Ability::Shield {
+            max_shield: 30.0,
+            regeneration: 50.0,
+            delay: 100.0,
+        };
+        let regeneration = Ability::Regeneration { per_second: 2.0 };
+        let state = Abilities::new(&[shield.clone(), regeneration.clone()]);
+        assert!(state.matches(&[shield.clone(), regeneration.clone()]));
+        assert!(!state.matches(&[regeneration, shield.clone()]));
+        assert!(!state.matches(&[shield]));
+    }
+}
@@ -0,0 +1,256 @@
+use crate::entity::damage::{Damage, DamageSource, DamageType};
+use crate::tick::{self, Tick};
+use serde::{Deserialize, Serialize};
+use std::mem;
+
+/// What a status effect does to the enemy it is applied to.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type")]
+pub enum EffectKind {
+    /// Reduces the movement speed by `amount` (0 to 1) of the current speed.
+    Slow { amount: f64 },
+    /// Stops the enemy from moving, e.g. a freeze.
+    Stun,
+    /// Deals damage every tick, e.g. burning or poison.
+    DamageOverTime {
+        damage_per_second: f64,
+        #[serde(default)]
+        damage_type: DamageType,
+    },
+    /// Increases all damage taken by `amount`, 0.5 meaning 50% more damage.
+    Vulnerability { amount: f64 },
+}
+
+/// How an effect behaves when the same source applies an effect of the same kind again while the
+/// first one is still active.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum Stacking {
+    /// The new effect replaces the active one, restarting its duration.
+    #[default]
+    Refresh,
+    /// Both effects are active, up to `max` at a time. The oldest one is dropped beyond that.
+    Stack { max: usize },
+    /// The new effect is dropped.
+    Ignore,
+}
+
+/// A timed effect on an enemy. Effects are part of the serialized enemy, so clients can render
+/// them.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StatusEffect {
+    kind: EffectKind,
+    /// The structure that applied the effect.
+    source: DamageSource,
+    stacking: Stacking,
+    /// Ticks until the effect wears off.
+    remaining: Tick,
+}
+
+impl StatusEffect {
+    pub fn new(
+        kind: EffectKind,
+        source: impl Into<DamageSource>,
+        stacking: Stacking,
+        duration: Tick,
+    ) -> Self {
+        Self {
+            kind,
+            source: source.into(),
+            stacking,
+            remaining: duration,
+        }
+    }
+
+    pub fn get_kind(&self) -> &EffectKind {
+        &self.kind
+    }
+
+    pub fn get_source(&self) -> &DamageSource {
+        &self.source
+    }
+
+    pub fn get_remaining(&self) -> Tick {
+        self.remaining
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.remaining == 0
+    }
+
+    /// Whether both effects come from the same source and are of the same kind, regardless of
+    /// their strength.
+    fn is_same(&self, other: &StatusEffect) -> bool {
+        self.source.get_structure() == other.source.get_structure()
+            && mem::discriminant(&self.kind) == mem::discriminant(&other.kind)
+    }
+
+    /// The damage the effect deals in a single tick.
+    fn tick_damage(&self) -> Option<Damage> {
+        match self.kind {
+            EffectKind::DamageOverTime {
+                damage_per_second,
+                damage_type,
+            } => Some(Damage::new(
+                damage_per_second * tick::DELTA_TIME,
+                damage_type,
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// The status effects currently active on an enemy.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(transparent)]
+pub struct StatusEffects {
+    effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    pub fn get_effects(&self) -> &[StatusEffect] {
+        &self.effects
+    }
+
+    /// Adds `effect` according to its stacking policy.
+    pub fn apply(&mut self, effect: StatusEffect) {
+        let same = self.effects.iter().filter(|e| e.is_same(&effect)).count();
+        if same == 0 {
+            self.effects.push(effect);
+            return;
+        }
+
+        match effect.stacking {
+            Stacking::Refresh => {
+                self.effects.retain(|e| !e.is_same(&effect));
+                self.effects.push(effect);
+            }
+            Stacking::Stack { max } => {
+                if same >= max {
+                    // Effects are kept in the order they were applied.
+                    if let Some(oldest) = self.effects.iter().position(|e| e.is_same(&effect)) {
+                        self.effects.remove(oldest);
+                    }
+                }
+                if max > 0 {
+                    self.effects.push(effect);
+                }
+            }
+            Stacking::Ignore => {}
+        }
+    }
+
+    /// Advances all effects by a tick, drops the expired ones and returns the damage dealt by
+    /// them during this tick along with the structure it comes from.
+    pub fn update(&mut self) -> Vec<(DamageSource, Damage)> {
+        let mut damage = vec![];
+        for effect in self.effects.iter_mut() {
+            damage.extend(effect.tick_damage().map(|d| (effect.source.clone(), d)));
+            effect.remaining = effect.remaining.saturating_sub(1);
+        }
+        self.effects.retain(|effect| !effect.is_expired());
+
+        damage
+    }
+
+    /// Factor the movement speed is multiplied with. Slows multiply with each other.
+    pub fn speed_multiplier(&self) -> f64 {
+        let mut multiplier = 1.0;
+        for effect in &self.effects {
+            match effect.kind {
+                EffectKind::Stun => return 0.0,
+                EffectKind::Slow { amount } => multiplier *= 1.0 - amount.clamp(0.0, 1.0),
+                _ => {}
+            }
+        }
+
+        multiplier
+    }
+
+    /// Factor the damage taken is multiplied with. Vulnerabilities add up.
+    pub fn damage_multiplier(&self) -> f64 {
+        let mut multiplier = 1.0;
+        for effect in &self.effects {
+            if let EffectKind::Vulnerability { amount } = effect.kind {
+                multiplier += amount;
+            }
+        }
+
+        multiplier
+    }
+}
+
+/// A status effect as written in a structure definition, applied to every enemy the structure
+/// damages.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EffectDefinition {
+    kind: EffectKind,
+    /// Duration in milliseconds.
+    duration: f64,
+    #[serde(default)]
+    stacking: Stacking,
+}
+
+impl EffectDefinition {
+    pub fn create(&self, source: impl Into<DamageSource>) -> StatusEffect {
+        StatusEffect::new(
+            self.kind.clone(),
+            source,
+            self.stacking,
+            tick::from_millis(self.duration),
+        )
+    }
+}
+
+#[cfg(test)]
+mod effect_tests {
+    use crate::entity::{
+        Damage, DamageSource, DamageType, EffectKind, Stacking, StatusEffect, StatusEffects,
+    };
+
+    fn slow(source: usize, amount: f64, stacking: Stacking, duration: u64) -> StatusEffect {
+        StatusEffect::new(EffectKind::Slow { amount }, source, stacking, duration)
+    }
+
+    #[test]
+    fn stacking() {
+        let mut effects = StatusEffects::default();
+        effects.apply(slow(1, 0.5, Stacking::Refresh, 10));
+        effects.apply(slow(1, 0.2, Stacking::Refresh, 20));
+        assert_eq!(effects.get_effects().len(), 1);
+        assert_eq!(effects.get_effects()[0].get_remaining(), 20);
+
+        // Effects of other sources are independent.
+        effects.apply(slow(2, 0.5, Stacking::Ignore, 10));
+        effects.apply(slow(2, 0.5, Stacking::Ignore, 30));
+        assert_eq!(effects.get_effects().len(), 2);
+        assert!((effects.speed_multiplier() - 0.4).abs() < 1e-9);
+
+        for duration in 1..=3 {
+            effects.apply(slow(3, 0.1, Stacking::Stack { max: 2 }, duration));
+        }
+        assert_eq!(effects.get_effects().len(), 4);
+        assert_eq!(effects.get_effects()[2].get_remaining(), 2);
+    }
+
+    #[test]
+    fn update() {
+        let mut effects = StatusEffects::default();
+        effects.apply(StatusEffect::new(
+            EffectKind::DamageOverTime {
+                damage_per_second: 50.0,
+                damage_type: DamageType::Fire,
+            },
+            1,
+            Stacking::Refresh,
+            2,
+        ));
+        effects.apply(StatusEffect::new(EffectKind::Stun, 1, Stacking::Refresh, 1));
+        assert_eq!(effects.speed_multiplier(), 0.0);
+
+        let fire = vec![(DamageSource::from(1), Damage::new(1.0, DamageType::Fire))];
+        assert_eq!(effects.update(), fire);
+        assert_eq!(effects.speed_multiplier(), 1.0);
+        assert_eq!(effects.update(), fire);
+        assert!(effects.get_effects().is_empty());
+    }
+}
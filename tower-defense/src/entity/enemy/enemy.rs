@@ -1,24 +1,34 @@
+use crate::entity::damage::{Damage, DamageSource, Resistances};
+use crate::entity::enemy::ability::{Abilities, Ability, AbilityEvent};
+use crate::entity::enemy::effect::{StatusEffect, StatusEffects};
 use crate::entity::enemy::enemy_type::EnemyType;
-use crate::entity::gif::GifFrames;
+use crate::entity::enemy::movement::Movement;
+use crate::entity::enemy::phase::Phase;
 use crate::map::Map;
 use crate::math::Vector2;
+use crate::tick::{self, Tick};
 use log::error;
 use serde::{Deserialize, Serialize};
-use std::sync::atomic::{AtomicUsize, Ordering};
 
 #[derive(Serialize, PartialEq, Deserialize)]
 #[serde(tag = "type", content = "data")]
 enum State {
     Idle,
-    Dying { time_of_death: f64 },
+    Dying { time_of_death: Tick },
     Dead,
 }
 
 impl State {
-    fn update(self, map: &'static Map, time: f64, enemy: &mut Enemy) -> Self {
+    fn update(
+        self,
+        map: &Map,
+        tick: Tick,
+        enemy: &mut Enemy,
+        events: &mut Vec<AbilityEvent>,
+    ) -> Self {
         match self {
-            Self::Idle => self.idle_update(map, time, enemy),
-            Self::Dying { time_of_death } => self.dying_update(time, time_of_death, enemy),
+            Self::Idle => self.idle_update(map, tick, enemy, events),
+            Self::Dying { time_of_death } => self.dying_update(tick, time_of_death, enemy),
             Self::Dead => {
                 error!("Cannot update dead enemy");
                 self
@@ -26,21 +36,74 @@ impl State {
         }
     }
 
-    fn idle_update(self, map: &'static Map, time: f64, enemy: &mut Enemy) -> Self {
+    fn idle_update(
+        self,
+        map: &Map,
+        tick: Tick,
+        enemy: &mut Enemy,
+        events: &mut Vec<AbilityEvent>,
+    ) -> Self {
+        let model = enemy.get_enemy_type().get_model();
+        // Enemies saved before their model had abilities start with fresh ones.
+        if !enemy.abilities.matches(model.get_abilities()) {
+            enemy.abilities = Abilities::new(model.get_abilities());
+        }
+        // Effects applied since the last update still count for this one.
+        let speed_multiplier = enemy.effects.speed_multiplier();
+        for (source, damage) in enemy.effects.update() {
+            enemy.apply_damage(damage, source);
+        }
         if enemy.health <= 0.0 {
+            for ability in model.get_abilities() {
+                if let Ability::Split { into, count } = ability {
+                    events.extend(enemy.spawn(into, *count));
+                }
+            }
             return State::Dying {
-                time_of_death: time,
+                time_of_death: tick,
             };
         }
-        let move_speed = enemy.get_enemy_type().get_model().get_move_speed();
-        let t = (time - enemy.get_spawn_time()) / 1000.0;
-        enemy.set_position(map.get_path().coords_at(t * move_speed));
+        // Heavy hits can skip phases, each of them is still entered.
+        while let Some(phase) = model.get_phases().get(enemy.phase) {
+            if enemy.health > phase.get_health() * model.get_max_health() {
+                break;
+            }
+            enemy.phase += 1;
+            events.push(AbilityEvent::PhaseChanged {
+                source: enemy.id,
+                phase: enemy.phase,
+            });
+            for minions in phase.get_minions() {
+                events.extend(enemy.spawn(minions.get_enemy(), minions.get_count()));
+            }
+        }
+        let (regenerated, heals) = enemy.abilities.update(model.get_abilities());
+        enemy.heal(regenerated);
+        for (radius, amount) in heals {
+            events.push(AbilityEvent::Heal {
+                source: enemy.id,
+                center: enemy.pos.clone(),
+                radius,
+                amount,
+            });
+        }
+        let move_speed = enemy.get_move_speed();
+        enemy.move_by(move_speed * speed_multiplier * tick::DELTA_TIME);
+        match map.get_route(enemy.get_path(), model.get_movement()) {
+            Some(path) => {
+                enemy.route_length = path.length();
+                enemy.set_position(path.coords_at(enemy.distance));
+            }
+            None => error!("Enemy {} is on unknown path {}", enemy.id, enemy.get_path()),
+        }
 
         Self::Idle
     }
 
-    fn dying_update(self, time: f64, time_of_death: f64, enemy: &Enemy) -> Self {
-        if (enemy.get_enemy_type().get_model().death_duration + time_of_death) < time {
+    fn dying_update(self, tick: Tick, time_of_death: Tick, enemy: &Enemy) -> Self {
+        let death_duration =
+            tick::from_millis(enemy.get_enemy_type().get_model().get_death_duration());
+        if (time_of_death + death_duration) < tick {
             return Self::Dead;
         }
 
@@ -48,111 +111,161 @@ impl State {
     }
 }
 
-#[derive(Serialize)]
-pub struct EnemyModel {
-    max_health: f64,
-    damage: u64,
-    move_speed: f64,
-    coin_reward: usize,
-    death_duration: f64,
-    idle_frames: GifFrames,
-    dying_frames: GifFrames,
-    idle_spritesheet: String,
-    dying_spritesheet: String,
-}
-
-impl EnemyModel {
-    pub(super) fn new(
-        max_health: f64,
-        damage: u64,
-        move_speed: f64,
-        coin_reward: usize,
-        death_duration: f64,
-        idle_frames: GifFrames,
-        dying_frames: GifFrames,
-        idle_spritesheet: String,
-        dying_spritesheet: String,
-    ) -> Self {
-        Self {
-            max_health,
-            damage,
-            move_speed,
-            coin_reward,
-            death_duration,
-            idle_frames,
-            dying_frames,
-            idle_spritesheet,
-            dying_spritesheet,
-        }
-    }
-
-    pub fn get_max_health(&self) -> f64 {
-        self.max_health
-    }
-
-    pub fn get_damage(&self) -> u64 {
-        self.damage
-    }
-
-    pub fn get_move_speed(&self) -> f64 {
-        self.move_speed
-    }
-
-    pub fn get_coin_reward(&self) -> usize {
-        self.coin_reward
-    }
-}
-
 #[derive(Serialize, Deserialize)]
 pub struct Enemy {
     id: usize,
     pos: Vector2,
     health: f64,
     enemy_type: EnemyType,
-    spawn_time: f64,
+    /// Name of the map path the enemy walks along.
+    path: String,
+    spawn_time: Tick,
+    /// How far the enemy has moved along its path.
+    #[serde(default)]
+    distance: f64,
+    /// Length of the route the enemy takes, which differs between paths and movements.
+    #[serde(default)]
+    route_length: f64,
+    #[serde(default)]
+    effects: StatusEffects,
+    /// The structure that dealt the killing blow.
+    #[serde(default)]
+    killed_by: Option<DamageSource>,
+    /// State of the abilities of the model, e.g. the remaining shield.
+    #[serde(default)]
+    abilities: Abilities,
+    /// The number of the boss phase the enemy is in, 0 before the first one.
+    #[serde(default)]
+    phase: usize,
     state: Option<State>,
 }
 
 impl Enemy {
-    pub(super) fn new(enemy_type: EnemyType, spawn_time: f64) -> Self {
-        static ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
-        let id = ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    pub(super) fn new(id: usize, enemy_type: EnemyType, path: String, spawn_time: Tick) -> Self {
         Self {
             id,
             pos: Vector2::new(0.0, 0.0),
             health: enemy_type.get_model().get_max_health(),
+            abilities: Abilities::new(enemy_type.get_model().get_abilities()),
             enemy_type,
+            path,
             spawn_time,
+            distance: 0.0,
+            route_length: 0.0,
+            effects: StatusEffects::default(),
+            killed_by: None,
+            phase: 0,
             state: Some(State::Idle),
         }
     }
 
+    pub fn get_id(&self) -> usize {
+        self.id
+    }
+
     pub fn get_enemy_type(&self) -> &EnemyType {
         &self.enemy_type
     }
 
-    pub fn get_spawn_time(&self) -> f64 {
+    /// Name of the map path the enemy was sent along. Flying enemies take the air route
+    /// belonging to it instead, see [`Map::get_route`].
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn get_movement(&self) -> Movement {
+        self.enemy_type.get_model().get_movement()
+    }
+
+    pub fn get_spawn_time(&self) -> Tick {
         self.spawn_time
     }
 
     pub fn is_alive(&self) -> bool {
-        match self.state.as_ref().unwrap() {
-            State::Idle => true,
-            _ => false,
-        }
+        matches!(self.state.as_ref().unwrap(), State::Idle)
     }
 
     pub fn is_dead(&self) -> bool {
-        match self.state.as_ref().unwrap() {
-            State::Dead => true,
-            _ => false,
-        }
+        matches!(self.state.as_ref().unwrap(), State::Dead)
+    }
+
+    /// Starts the enemy at `distance` along its route of `route_length` instead of at the
+    /// beginning, e.g. when it split off another enemy.
+    pub(crate) fn with_progress(mut self, distance: f64, route_length: f64, pos: Vector2) -> Self {
+        self.distance = distance;
+        self.route_length = route_length;
+        self.pos = pos;
+        self
     }
 
-    pub fn update(&mut self, time: f64, map: &'static Map) {
+    /// Advances the enemy by a tick. Returns what its abilities do to other enemies, which the
+    /// game has to apply.
+    pub fn update(&mut self, tick: Tick, map: &Map) -> Vec<AbilityEvent> {
+        let mut events = vec![];
         if let Some(state) = self.state.take() {
-            self.state = Some(state.update(map, time, self));
+            self.state = Some(state.update(map, tick, self, &mut events));
         }
+        events
+    }
+
+    /// How far the enemy has moved along its path.
+    pub fn get_distance(&self) -> f64 {
+        self.distance
+    }
+
+    /// How far the enemy still has to go until the end of its route. Unlike the distance it can
+    /// be compared between enemies on routes of different lengths.
+    pub fn get_remaining_distance(&self) -> f64 {
+        (self.route_length - self.distance).max(0.0)
+    }
+
+    /// The current movement speed, taking status effects into account.
+    pub fn get_speed(&self) -> f64 {
+        self.get_move_speed() * self.effects.speed_multiplier()
+    }
+
+    /// The number of the boss phase the enemy is in, 0 before the first one.
+    pub fn get_phase(&self) -> usize {
+        self.phase
+    }
+
+    fn get_current_phase(&self) -> Option<&'static Phase> {
+        let phases = self.enemy_type.get_model().get_phases();
+        self.phase.checked_sub(1).and_then(|i| phases.get(i))
+    }
+
+    /// The move speed of the model or the current phase, without status effects.
+    fn get_move_speed(&self) -> f64 {
+        self.get_current_phase()
+            .and_then(|phase| phase.get_move_speed())
+            .unwrap_or_else(|| self.enemy_type.get_model().get_move_speed())
+    }
+
+    fn get_resistances(&self) -> &'static Resistances {
+        self.get_current_phase()
+            .and_then(|phase| phase.get_resistances())
+            .unwrap_or_else(|| self.enemy_type.get_model().get_resistances())
+    }
+
+    /// Spawns `count` enemies of the type `into` where this enemy currently is.
+    fn spawn(&self, into: &str, count: u32) -> Option<AbilityEvent> {
+        // Validated when the models are loaded.
+        let into = EnemyType::try_from(String::from(into)).ok()?;
+        Some(AbilityEvent::Spawn {
+            into,
+            count,
+            path: self.path.clone(),
+            movement: self.get_movement(),
+            distance: self.distance,
+            pos: self.pos.clone(),
+        })
+    }
+
+    /// Moves the enemy along its path, backwards for negative distances, e.g. when it is knocked
+    /// back. The enemy never moves back past the start of its path. The position follows with
+    /// the next update.
+    pub fn move_by(&mut self, distance: f64) {
+        self.distance = (self.distance + distance).max(0.0);
     }
 
     fn set_position(&mut self, new_pos: Vector2) {
@@ -163,11 +276,129 @@ impl Enemy {
         self.health
     }
 
-    pub fn apply_damage(&mut self, damage: f64) {
-        self.health -= damage;
+    /// Restores up to `amount` health, never above the maximum health. Dying enemies cannot be
+    /// healed.
+    pub fn heal(&mut self, amount: f64) {
+        if self.health > 0.0 {
+            let max_health = self.enemy_type.get_model().get_max_health();
+            self.health = (self.health + amount).min(max_health);
+        }
+    }
+
+    /// The shield points left, which absorb damage before the health.
+    pub fn get_shield(&self) -> f64 {
+        self.abilities.get_shield()
+    }
+
+    pub fn get_abilities(&self) -> &Abilities {
+        &self.abilities
+    }
+
+    /// The structure that killed the enemy, if it has been killed.
+    pub fn get_killed_by(&self) -> Option<&DamageSource> {
+        self.killed_by.as_ref()
+    }
+
+    /// Applies armor, resistances and vulnerabilities to `damage` dealt by the structure
+    /// `source`, subtracts the result from the shield and then the health of the enemy and
+    /// returns the damage actually dealt. Damage beyond the remaining health is not counted.
+    pub fn apply_damage(&mut self, damage: Damage, source: impl Into<DamageSource>) -> f64 {
+        if self.health <= 0.0 {
+            return 0.0;
+        }
+        let model = self.enemy_type.get_model();
+        let amount = damage.mitigate(model.get_armor(), self.get_resistances())
+            * self.effects.damage_multiplier();
+        let remaining = self.abilities.absorb(amount);
+        let dealt = amount - remaining + remaining.min(self.health);
+        self.health -= remaining;
+        if self.health <= 0.0 {
+            self.killed_by = Some(source.into());
+        }
+
+        dealt
+    }
+
+    /// Adds a status effect, or combines it with an active one of the same source according to
+    /// its stacking policy.
+    pub fn apply_effect(&mut self, effect: StatusEffect) {
+        self.effects.apply(effect);
+    }
+
+    pub fn get_effects(&self) -> &[StatusEffect] {
+        self.effects.get_effects()
     }
 
     pub fn get_position(&self) -> &Vector2 {
         &self.pos
     }
 }
+
+#[cfg(test)]
+mod enemy_tests {
+    use crate::entity::{
+        AbilityEvent, Damage, DamageType, EffectKind, EnemyType, Stacking, StatusEffect,
+    };
+    use crate::map::levels::{get_default_level, LEVEL_REGISTRY};
+    use crate::tick;
+
+    #[test]
+    fn movement() {
+        let map = &LEVEL_REGISTRY[get_default_level()];
+        let enemy_type = EnemyType::new_unchecked("Blue");
+        let step = enemy_type.get_model().get_move_speed() * tick::DELTA_TIME;
+        let mut enemy = enemy_type.create(0, String::from("main"), 0);
+
+        for tick in 0..10 {
+            enemy.update(tick, map);
+        }
+        assert!((enemy.get_distance() - 10.0 * step).abs() < 1e-9);
+
+        enemy.apply_effect(StatusEffect::new(
+            EffectKind::Slow { amount: 0.5 },
+            0,
+            Stacking::Refresh,
+            10,
+        ));
+        for tick in 10..20 {
+            enemy.update(tick, map);
+        }
+        assert!((enemy.get_distance() - 15.0 * step).abs() < 1e-9);
+        let path = map.get_path("main").unwrap();
+        assert!((enemy.get_position() - &path.coords_at(15.0 * step)).magnitude() < 1e-9);
+
+        enemy.move_by(-100.0 * step);
+        assert_eq!(enemy.get_distance(), 0.0);
+    }
+
+    #[test]
+    fn boss_phases() {
+        let map = &LEVEL_REGISTRY[get_default_level()];
+        let enemy_type = EnemyType::new_unchecked("PurpleKing");
+        let max_health = enemy_type.get_model().get_max_health();
+        let mut enemy = enemy_type.create(0, String::from("main"), 0);
+        let speed = enemy.get_speed();
+
+        // A single hit down to 20% health enters both phases at once.
+        enemy.apply_damage(Damage::new(0.8 * max_health, DamageType::Fire), 0);
+        let events = enemy.update(0, map);
+        assert_eq!(enemy.get_phase(), 2);
+        assert!(enemy.get_speed() > speed);
+        let phases: Vec<_> = events
+            .iter()
+            .filter_map(|event| match event {
+                AbilityEvent::PhaseChanged { phase, .. } => Some(*phase),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(phases, vec![1, 2]);
+        let minions: u32 = events
+            .iter()
+            .filter_map(|event| match event {
+                AbilityEvent::Spawn { count, .. } => Some(*count),
+                _ => None,
+            })
+            .sum();
+        assert_eq!(minions, 6);
+    }
+}
@@ -1,66 +1,63 @@
-use crate::entity::enemy::enemy::{Enemy, EnemyModel};
-use crate::entity::enemy::instance::{
-    register_blue_model, register_purple_model, register_red_model, BLUE_MODEL, PURPLE_MODEL,
-    RED_MODEL,
-};
-use rand::Rng;
+use crate::entity::enemy::enemy::Enemy;
+use crate::entity::enemy::model::{load_enemy_models, EnemyModel, EnemyModelMap};
+use crate::resources::RESOURCES_DIR;
+use crate::tick::Tick;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use strum::IntoEnumIterator;
-use strum_macros::EnumIter;
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::Path;
 
-pub type EnemyModelMap = HashMap<String, &'static EnemyModel>;
-
-#[derive(Serialize, Copy, Clone, Deserialize, Debug, EnumIter)]
-pub enum EnemyType {
-    Blue,
-    Purple,
-    Red,
-}
+/// Identifies an enemy model by its id. Deserializing an enemy type fails for ids that are not
+/// defined in the resources.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "String", into = "String")]
+pub struct EnemyType(String);
 
 impl EnemyType {
-    pub fn new(self, spawn_time: f64) -> Enemy {
-        Enemy::new(self, spawn_time)
+    /// Only for ids that have already been validated against [`ENEMY_MODEL_MAP`].
+    #[cfg(test)]
+    pub(crate) fn new_unchecked(id: &str) -> Self {
+        Self(String::from(id))
+    }
+
+    pub fn create(&self, id: usize, path: String, spawn_time: Tick) -> Enemy {
+        Enemy::new(id, self.clone(), path, spawn_time)
     }
 
     pub fn get_model(&self) -> &'static EnemyModel {
-        match self {
-            EnemyType::Blue => &*BLUE_MODEL,
-            EnemyType::Purple => &*PURPLE_MODEL,
-            EnemyType::Red => &*RED_MODEL,
-        }
+        &ENEMY_MODEL_MAP[&self.0]
     }
+}
+
+impl TryFrom<String> for EnemyType {
+    type Error = String;
 
-    pub fn random() -> EnemyType {
-        let rng = rand::thread_rng().gen_range(0..3);
-        match rng {
-            0 => EnemyType::Blue,
-            1 => EnemyType::Purple,
-            2 => EnemyType::Red,
-            _ => panic!(),
+    fn try_from(id: String) -> Result<Self, Self::Error> {
+        if ENEMY_MODEL_MAP.contains_key(&id) {
+            Ok(Self(id))
+        } else {
+            Err(format!("Unknown enemy type: {}", id))
         }
     }
+}
 
-    fn register_model(&self, model_map: &mut EnemyModelMap) {
-        match self {
-            EnemyType::Blue => register_blue_model(model_map),
-            EnemyType::Purple => register_purple_model(model_map),
-            EnemyType::Red => register_red_model(model_map),
-        }
+impl From<EnemyType> for String {
+    fn from(enemy_type: EnemyType) -> Self {
+        enemy_type.0
+    }
+}
+
+impl fmt::Display for EnemyType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
     }
 }
 
 /****************************************
-* Structure Map
+* Enemy Map
 *****************************************/
 
 lazy_static! {
-    pub static ref ENEMY_MODEL_MAP: EnemyModelMap = {
-        let mut map: EnemyModelMap = HashMap::new();
-        for enemy_type in EnemyType::iter() {
-            enemy_type.register_model(&mut map);
-        }
-
-        map
-    };
+    pub static ref ENEMY_MODEL_MAP: EnemyModelMap = load_enemy_models(Path::new(RESOURCES_DIR))
+        .unwrap_or_else(|e| panic!("Could not load enemy models: {}", e));
 }
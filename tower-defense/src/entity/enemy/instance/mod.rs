@@ -1,7 +0,0 @@
-mod blue;
-mod purple;
-mod red;
-
-pub(super) use blue::{register_blue_model, BLUE_MODEL};
-pub(super) use purple::{register_purple_model, PURPLE_MODEL};
-pub(super) use red::{register_red_model, RED_MODEL};
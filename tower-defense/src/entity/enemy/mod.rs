@@ -1,7 +1,16 @@
+mod ability;
+mod effect;
+#[allow(clippy::module_inception)]
 mod enemy;
 mod enemy_type;
-mod instance;
+mod model;
+mod movement;
+mod phase;
 
+pub use ability::{Abilities, Ability, AbilityEvent, AbilityState};
+pub use effect::{EffectDefinition, EffectKind, Stacking, StatusEffect, StatusEffects};
 pub use enemy::Enemy;
 pub use enemy_type::{EnemyType, ENEMY_MODEL_MAP};
-use instance::*;
+pub use model::EnemyModel;
+pub use movement::Movement;
+pub use phase::{Minions, Phase};
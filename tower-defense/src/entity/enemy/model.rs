@@ -0,0 +1,248 @@
+use crate::entity::damage::{DamageType, Resistances};
+use crate::entity::enemy::ability::Ability;
+use crate::entity::enemy::movement::Movement;
+use crate::entity::enemy::phase::Phase;
+use crate::entity::gif::GifFrames;
+use crate::resources::{load_gif_frames, read_json_dir, ResourceError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Enemy models by id. Ordered so that `/enemies` lists them in the same order on every start.
+pub type EnemyModelMap = BTreeMap<String, EnemyModel>;
+
+/****************************************
+* Enemy Model
+*****************************************/
+
+/// The shared, immutable description of an enemy archetype. Models are read from the json files
+/// in `resources/enemies` and serialized as is to the clients.
+#[derive(Serialize, Deserialize)]
+pub struct EnemyModel {
+    id: String,
+    max_health: f64,
+    damage: u64,
+    move_speed: f64,
+    #[serde(default)]
+    movement: Movement,
+    /// Subtracted from every physical hit.
+    #[serde(default)]
+    armor: f64,
+    #[serde(default)]
+    resistances: Resistances,
+    coin_reward: usize,
+    /// Time in ms the dying animation is shown before the enemy is removed.
+    death_duration: f64,
+    #[serde(default)]
+    abilities: Vec<Ability>,
+    /// Bosses are announced to the clients when they spawn, change phase and die.
+    #[serde(default)]
+    boss: bool,
+    #[serde(default)]
+    phases: Vec<Phase>,
+
+    idle_spritesheet: String,
+    dying_spritesheet: String,
+    #[serde(skip_serializing)]
+    idle_frames_file: String,
+    #[serde(skip_serializing)]
+    dying_frames_file: String,
+    #[serde(skip_deserializing)]
+    idle_frames: GifFrames,
+    #[serde(skip_deserializing)]
+    dying_frames: GifFrames,
+}
+
+impl EnemyModel {
+    pub fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn get_max_health(&self) -> f64 {
+        self.max_health
+    }
+
+    pub fn get_damage(&self) -> u64 {
+        self.damage
+    }
+
+    pub fn get_move_speed(&self) -> f64 {
+        self.move_speed
+    }
+
+    pub fn get_movement(&self) -> Movement {
+        self.movement
+    }
+
+    pub fn get_armor(&self) -> f64 {
+        self.armor
+    }
+
+    pub fn get_resistances(&self) -> &Resistances {
+        &self.resistances
+    }
+
+    pub fn get_resistance(&self, damage_type: DamageType) -> f64 {
+        self.resistances.get(&damage_type).copied().unwrap_or(0.0)
+    }
+
+    pub fn get_coin_reward(&self) -> usize {
+        self.coin_reward
+    }
+
+    pub fn get_death_duration(&self) -> f64 {
+        self.death_duration
+    }
+
+    pub fn get_abilities(&self) -> &[Ability] {
+        &self.abilities
+    }
+
+    pub fn is_boss(&self) -> bool {
+        self.boss
+    }
+
+    /// The phases of a boss fight in the order they are entered.
+    pub fn get_phases(&self) -> &[Phase] {
+        &self.phases
+    }
+
+    /// Ids of the enemies this enemy splits into when killed.
+    fn split_targets(&self) -> impl Iterator<Item = &str> {
+        self.abilities.iter().filter_map(|ability| match ability {
+            Ability::Split { into, .. } => Some(into.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Ids of the enemies spawned by the phases.
+    fn minions(&self) -> impl Iterator<Item = &str> {
+        self.phases
+            .iter()
+            .flat_map(|phase| phase.get_minions())
+            .map(|minions| minions.get_enemy())
+    }
+}
+
+/****************************************
+* Loading
+*****************************************/
+
+/// Reads all enemy definitions in `<resources>/enemies` and the sprite frames they reference.
+pub fn load_enemy_models(resources: &Path) -> Result<EnemyModelMap, ResourceError> {
+    let dir = resources.join("enemies");
+    let mut map = EnemyModelMap::new();
+    for (file, mut model) in read_json_dir::<EnemyModel>(&dir)? {
+        let path = dir.join(format!("{}.json", file));
+
+        if model.max_health <= 0.0 {
+            return Err(ResourceError::new(
+                &path,
+                String::from("max_health must be positive"),
+            ));
+        }
+        if model
+            .resistances
+            .values()
+            .any(|resistance| *resistance > 1.0)
+        {
+            return Err(ResourceError::new(
+                &path,
+                String::from("Resistances must not be above 1"),
+            ));
+        }
+        let mut threshold = 1.0;
+        for phase in &model.phases {
+            // A phase at 0 health would only be entered once the boss is dead.
+            if phase.get_health() <= 0.0 || phase.get_health() >= threshold {
+                return Err(ResourceError::new(
+                    &path,
+                    String::from("Phases must start at decreasing health between 0 and 1"),
+                ));
+            }
+            threshold = phase.get_health();
+        }
+
+        model.idle_frames = load_gif_frames(resources, &model.idle_frames_file)?;
+        model.dying_frames = load_gif_frames(resources, &model.dying_frames_file)?;
+
+        if map.contains_key(&model.id) {
+            return Err(ResourceError::new(
+                &path,
+                format!("Duplicate enemy id {}", model.id),
+            ));
+        }
+        map.insert(model.id.clone(), model);
+    }
+
+    for model in map.values() {
+        let path = dir.join(format!("{}.json", model.id));
+        for into in model.split_targets().chain(model.minions()) {
+            if !map.contains_key(into) {
+                return Err(ResourceError::new(
+                    &path,
+                    format!("Cannot spawn unknown enemy {}", into),
+                ));
+            }
+        }
+        if splits_into(&map, &model.id, &model.id, &mut vec![]) {
+            return Err(ResourceError::new(
+                &path,
+                String::from("The enemy splits into itself"),
+            ));
+        }
+    }
+
+    Ok(map)
+}
+
+/// Whether killing `id` eventually spawns `target`, following every split.
+fn splits_into<'a>(
+    map: &'a EnemyModelMap,
+    id: &str,
+    target: &str,
+    visited: &mut Vec<&'a str>,
+) -> bool {
+    for into in map[id].split_targets() {
+        if into == target {
+            return true;
+        }
+        if !visited.contains(&into) {
+            visited.push(into);
+            if splits_into(map, into, target, visited) {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod model_tests {
+    use crate::entity::enemy::model::load_enemy_models;
+    use crate::entity::{Ability, DamageType, Movement};
+    use crate::resources::RESOURCES_DIR;
+    use std::path::Path;
+
+    #[test]
+    fn load_resources() {
+        let models =
+            load_enemy_models(Path::new(RESOURCES_DIR)).unwrap_or_else(|e| panic!("{}", e));
+
+        assert_eq!(
+            models.keys().collect::<Vec<_>>(),
+            vec!["Blue", "Purple", "PurpleKing", "Red"]
+        );
+        assert_eq!(models["Red"].get_move_speed(), 120.0);
+        assert_eq!(models["Red"].get_movement(), Movement::Flying);
+        assert_eq!(models["Blue"].get_movement(), Movement::Ground);
+        assert_eq!(models["Purple"].get_death_duration(), 500.0);
+        assert_eq!(models["Red"].get_resistance(DamageType::Electric), 0.5);
+        assert_eq!(models["Red"].get_resistance(DamageType::Physical), 0.0);
+        assert!(matches!(
+            models["Purple"].get_abilities(),
+            [Ability::Heal { .. }, Ability::Split { .. }]
+        ));
+    }
+}
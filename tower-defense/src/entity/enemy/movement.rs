@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// How an enemy gets to the base. Towers only hit the kinds of enemies their model can reach.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Movement {
+    /// Walks along its path.
+    #[default]
+    Ground,
+    /// Flies along the air route of the map or, if it has none, straight from the start of its
+    /// path to the end.
+    Flying,
+}
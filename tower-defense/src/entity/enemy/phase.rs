@@ -0,0 +1,51 @@
+use crate::entity::damage::Resistances;
+use serde::{Deserialize, Serialize};
+
+/// Enemies spawned next to a boss when it enters a phase.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Minions {
+    enemy: String,
+    count: u32,
+}
+
+impl Minions {
+    pub fn get_enemy(&self) -> &str {
+        &self.enemy
+    }
+
+    pub fn get_count(&self) -> u32 {
+        self.count
+    }
+}
+
+/// A stage of a boss fight. Phases are entered in order once the health of the boss drops to
+/// the given share of its maximum health and replace the stats of the model they set.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Phase {
+    /// Share of the maximum health at which the phase starts, between 0 and 1.
+    health: f64,
+    #[serde(default)]
+    move_speed: Option<f64>,
+    #[serde(default)]
+    resistances: Option<Resistances>,
+    #[serde(default)]
+    minions: Vec<Minions>,
+}
+
+impl Phase {
+    pub fn get_health(&self) -> f64 {
+        self.health
+    }
+
+    pub fn get_move_speed(&self) -> Option<f64> {
+        self.move_speed
+    }
+
+    pub fn get_resistances(&self) -> Option<&Resistances> {
+        self.resistances.as_ref()
+    }
+
+    pub fn get_minions(&self) -> &[Minions] {
+        &self.minions
+    }
+}
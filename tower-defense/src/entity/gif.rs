@@ -25,7 +25,7 @@ pub struct GifFrame {
     sourceSize: Size,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct GifFrames {
     frames: Vec<GifFrame>,
 }
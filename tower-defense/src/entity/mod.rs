@@ -1,6 +1,11 @@
+mod damage;
 mod enemy;
 mod gif;
+mod projectile;
 mod structure;
 
+pub use damage::{Damage, DamageSource, DamageType, Resistances};
 pub use enemy::*;
+pub(crate) use gif::GifFrames;
+pub use projectile::{Motion, Payload, Projectile, ProjectileModel};
 pub use structure::*;
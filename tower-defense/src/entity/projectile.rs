@@ -0,0 +1,369 @@
+use crate::entity::{DamageSource, Enemy, StructureModel, StructureType};
+use crate::math::{distance_to_segment, SpatialIndex, Vector2};
+use crate::tick::{self, Tick};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Formatter};
+
+/// How projectiles fired by a tower fly and what they do on impact. Part of the structure model.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProjectileModel {
+    /// Units per second.
+    speed: f64,
+    /// Distance within which the projectile hits an enemy.
+    radius: f64,
+    /// Time in ms after which the projectile disappears if it did not hit anything.
+    lifetime: f64,
+    /// Whether the projectile follows its target instead of flying in a straight line.
+    #[serde(default)]
+    homing: bool,
+    /// If positive, the projectile damages every enemy within this distance of the impact.
+    #[serde(default)]
+    splash_radius: f64,
+}
+
+impl ProjectileModel {
+    pub fn get_splash_radius(&self) -> f64 {
+        self.splash_radius
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.speed <= 0.0 {
+            return Err(String::from("The projectile speed must be greater than 0"));
+        }
+        if self.lifetime <= 0.0 {
+            return Err(String::from(
+                "The projectile lifetime must be greater than 0",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for ProjectileModel {
+    fn default() -> Self {
+        Self {
+            speed: 300.0,
+            radius: 10.0,
+            lifetime: 1000.0,
+            homing: false,
+            splash_radius: 0.0,
+        }
+    }
+}
+
+/// How a projectile moves.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type")]
+pub enum Motion {
+    /// Keeps its velocity.
+    Straight,
+    /// Turns towards the enemy with the given id every tick. Continues in a straight line once
+    /// the target is gone.
+    Homing { target: usize },
+}
+
+/// What a projectile does when it hits, which is the attack described by the model of the tower
+/// that fired it. Only the id of the model is saved.
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(from = "StructureType", into = "StructureType")]
+pub struct Payload {
+    model: &'static StructureModel,
+}
+
+impl Payload {
+    pub fn new(model: &'static StructureModel) -> Self {
+        Self { model }
+    }
+}
+
+impl From<StructureType> for Payload {
+    fn from(structure_type: StructureType) -> Self {
+        Self::new(structure_type.get_model())
+    }
+}
+
+impl From<Payload> for StructureType {
+    fn from(payload: Payload) -> Self {
+        StructureType::new_unchecked(String::from(payload.model.get_id()))
+    }
+}
+
+impl fmt::Debug for Payload {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Payload")
+            .field(&self.model.get_id())
+            .finish()
+    }
+}
+
+/// A projectile flying through the map independently of the tower that fired it.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Projectile {
+    id: usize,
+    /// The structure that fired the projectile.
+    source: DamageSource,
+    pos: Vector2,
+    /// Units per second.
+    velocity: Vector2,
+    motion: Motion,
+    radius: f64,
+    /// Ticks until the projectile disappears.
+    remaining: Tick,
+    payload: Payload,
+}
+
+impl Projectile {
+    /// Creates a projectile flying from `pos` towards `target` as described by the model of the
+    /// payload. The id is handed out by the game once the projectile is added to it.
+    pub fn fire(
+        source: impl Into<DamageSource>,
+        pos: Vector2,
+        target: &Enemy,
+        payload: Payload,
+    ) -> Self {
+        let model = payload.model.get_projectile();
+        let velocity = velocity_towards(&pos, target.get_position(), model.speed);
+        let motion = if model.homing {
+            Motion::Homing {
+                target: target.get_id(),
+            }
+        } else {
+            Motion::Straight
+        };
+        Self {
+            id: 0,
+            source: source.into(),
+            pos,
+            velocity,
+            motion,
+            radius: model.radius,
+            remaining: tick::from_millis(model.lifetime),
+            payload,
+        }
+    }
+
+    pub fn get_id(&self) -> usize {
+        self.id
+    }
+
+    pub(crate) fn set_id(&mut self, id: usize) {
+        self.id = id;
+    }
+
+    pub fn get_source(&self) -> &DamageSource {
+        &self.source
+    }
+
+    pub fn get_position(&self) -> &Vector2 {
+        &self.pos
+    }
+
+    /// Moves the projectile by a tick and applies its payload if it hit an enemy on the way.
+    /// Returns `false` once the projectile is used up or its lifetime is over. `index` has to
+    /// contain the positions of `enemies`.
+    pub fn update(&mut self, enemies: &mut [Enemy], index: &SpatialIndex) -> bool {
+        if self.remaining == 0 {
+            return false;
+        }
+        self.remaining -= 1;
+
+        if let Motion::Homing { target } = self.motion {
+            match enemies
+                .iter()
+                .find(|enemy| enemy.get_id() == target && enemy.is_alive())
+            {
+                Some(enemy) => {
+                    self.velocity =
+                        velocity_towards(&self.pos, enemy.get_position(), self.velocity.magnitude())
+                }
+                None => self.motion = Motion::Straight,
+            }
+        }
+
+        // Every enemy touched along the way of this tick counts, not only those close to the new
+        // position, so fast projectiles cannot skip over enemies.
+        let start = self.pos.clone();
+        let end = &start + &(&self.velocity * tick::DELTA_TIME);
+        let middle = &(&start + &end) * 0.5;
+        let reach = (&end - &start).magnitude() / 2.0 + self.radius;
+        let hit = index
+            .within(&middle, reach)
+            .into_iter()
+            .map(|i| &enemies[i])
+            .filter(|enemy| {
+                self.payload.model.get_reach().can_hit(enemy)
+                    && distance_to_segment(enemy.get_position(), &start, &end) < self.radius
+            })
+            .min_by(|a, b| {
+                let a = (a.get_position() - &start).magnitude();
+                let b = (b.get_position() - &start).magnitude();
+                a.total_cmp(&b)
+            })
+            .map(|enemy| (enemy.get_id(), enemy.get_position().clone()));
+
+        match hit {
+            Some((id, impact)) => {
+                self.pos = impact;
+                self.hit(id, enemies, index);
+                false
+            }
+            None => {
+                self.pos = end;
+                true
+            }
+        }
+    }
+
+    fn hit(&self, target: usize, enemies: &mut [Enemy], index: &SpatialIndex) {
+        let model = self.payload.model;
+        let splash_radius = model.get_projectile().get_splash_radius();
+        let hit = if splash_radius > 0.0 {
+            let mut hit = index.within(&self.pos, splash_radius);
+            hit.retain(|&i| model.get_reach().can_hit(&enemies[i]));
+            hit
+        } else {
+            enemies
+                .iter()
+                .position(|enemy| enemy.get_id() == target)
+                .into_iter()
+                .collect()
+        };
+        for i in hit {
+            let enemy = &mut enemies[i];
+            enemy.apply_damage(model.get_attack(), self.source.clone());
+            for effect in model.get_effects() {
+                enemy.apply_effect(effect.create(self.source.clone()));
+            }
+        }
+    }
+}
+
+fn velocity_towards(from: &Vector2, to: &Vector2, speed: f64) -> Vector2 {
+    let direction = to - from;
+    let distance = direction.magnitude();
+    if distance == 0.0 {
+        return Vector2::new(0.0, 0.0);
+    }
+    &direction * (speed / distance)
+}
+
+#[cfg(test)]
+mod projectile_tests {
+    use crate::entity::{EnemyType, Payload, Projectile, StructureModel, StructureType};
+    use crate::math::{SpatialIndex, Vector2};
+    use serde_json::{json, Value};
+
+    /// The Single Shot Tower with parts of its definition replaced by `overrides`.
+    fn model(overrides: Value) -> &'static StructureModel {
+        let single_shot = StructureType::try_from(String::from("SingleShotTowerV1")).unwrap();
+        let mut model = serde_json::to_value(single_shot.get_model()).unwrap();
+        for (key, value) in overrides.as_object().unwrap() {
+            model[key] = value.clone();
+        }
+        // The frames are not needed here, only the names of their files have to be present.
+        model["idle_frames_file"] = json!("");
+        model["attack_frames_file"] = json!("");
+        Box::leak(Box::new(serde_json::from_value(model).unwrap()))
+    }
+
+    #[test]
+    fn swept_collision() {
+        let enemy_type = EnemyType::new_unchecked("Blue");
+        let max_health = enemy_type.get_model().get_max_health();
+        let mut enemies = vec![enemy_type.create(7, String::from("main"), 0)];
+        let mut index = SpatialIndex::new(1920.0, 1080.0, 100.0);
+        index.rebuild(enemies.iter().map(|enemy| enemy.get_position()));
+        let payload = Payload::new(model(json!({
+            "attack_damage": 10.0,
+            "projectile": { "speed": 300.0, "radius": 5.2, "lifetime": 1000.0 }
+        })));
+
+        // The enemy stands at the origin and the projectile moves 6 units per tick. It passes the
+        // enemy in between two positions, both of which are out of its radius.
+        let mut projectile = Projectile::fire(0, Vector2::new(-10.0, 5.0), &enemies[0], payload);
+        projectile.velocity = Vector2::new(300.0, 0.0);
+        assert!(projectile.update(&mut enemies, &index));
+        assert!(!projectile.update(&mut enemies, &index));
+        assert_eq!(enemies[0].get_health(), max_health - 10.0);
+
+        let mut missed = Projectile::fire(0, Vector2::new(100.0, 100.0), &enemies[0], payload);
+        missed.velocity = Vector2::new(0.0, 300.0);
+        let lifetime = missed.remaining;
+        for _ in 0..lifetime {
+            assert!(missed.update(&mut enemies, &index));
+        }
+        assert!(!missed.update(&mut enemies, &index));
+    }
+
+    #[test]
+    fn homing() {
+        let enemy_type = EnemyType::new_unchecked("Blue");
+        let max_health = enemy_type.get_model().get_max_health();
+        let place = |pos: Vector2| {
+            enemy_type
+                .create(7, String::from("main"), 0)
+                .with_progress(0.0, 0.0, pos)
+        };
+        let mut enemies = vec![place(Vector2::new(100.0, 0.0))];
+        let fire = |homing: bool| {
+            let payload = Payload::new(model(json!({
+                "attack_damage": 10.0,
+                "projectile": {
+                    "speed": 300.0,
+                    "radius": 10.0,
+                    "lifetime": 1000.0,
+                    "homing": homing
+                }
+            })));
+            Projectile::fire(0, Vector2::new(0.0, 0.0), &enemies[0], payload)
+        };
+        let mut homing = fire(true);
+        let mut straight = fire(false);
+
+        // The target moves away from where both projectiles were aimed at.
+        enemies[0] = place(Vector2::new(100.0, 100.0));
+        let mut index = SpatialIndex::new(1920.0, 1080.0, 100.0);
+        index.rebuild(enemies.iter().map(|enemy| enemy.get_position()));
+
+        while straight.update(&mut enemies, &index) {}
+        assert_eq!(enemies[0].get_health(), max_health);
+        while homing.update(&mut enemies, &index) {}
+        assert_eq!(enemies[0].get_health(), max_health - 10.0);
+    }
+
+    #[test]
+    fn splash() {
+        let enemy_type = EnemyType::new_unchecked("Blue");
+        let max_health = enemy_type.get_model().get_max_health();
+        let mut enemies: Vec<_> = [0.0, 30.0, 60.0]
+            .into_iter()
+            .enumerate()
+            .map(|(id, y)| {
+                enemy_type
+                    .create(id, String::from("main"), 0)
+                    .with_progress(0.0, 0.0, Vector2::new(100.0, y))
+            })
+            .collect();
+        let mut index = SpatialIndex::new(1920.0, 1080.0, 100.0);
+        index.rebuild(enemies.iter().map(|enemy| enemy.get_position()));
+        let payload = Payload::new(model(json!({
+            "attack_damage": 10.0,
+            "projectile": {
+                "speed": 300.0,
+                "radius": 10.0,
+                "lifetime": 1000.0,
+                "splash_radius": 40.0
+            }
+        })));
+
+        // Only the first enemy is in the way of the projectile, the second one is within the
+        // splash radius of the impact.
+        let mut projectile = Projectile::fire(0, Vector2::new(0.0, 0.0), &enemies[0], payload);
+        while projectile.update(&mut enemies, &index) {}
+        let health: Vec<_> = enemies.iter().map(|enemy| enemy.get_health()).collect();
+        assert_eq!(
+            health,
+            vec![max_health - 10.0, max_health - 10.0, max_health]
+        );
+    }
+}
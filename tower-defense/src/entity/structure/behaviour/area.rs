@@ -0,0 +1,50 @@
+use crate::entity::structure::tower::{AttackBehaviour, Tower};
+use crate::entity::{Enemy, Projectile};
+use crate::math::SpatialIndex;
+use crate::tick::{self, Tick};
+use serde::{Deserialize, Serialize};
+
+/// Damages every enemy within range at once, e.g. the Lightning Tower and the Konfetti Kanone.
+/// The damage is dealt `attack_damage_delay` after the attack started.
+#[derive(Serialize, Deserialize)]
+pub struct AreaAttack {
+    did_attack: bool,
+}
+
+impl AttackBehaviour for AreaAttack {
+    fn start(
+        tower: &Tower<Self>,
+        enemies: &[Enemy],
+        index: &SpatialIndex,
+        _: Tick,
+    ) -> Option<Self> {
+        let in_range = tower.enemies_in_range(enemies, index);
+        (!in_range.is_empty()).then_some(Self { did_attack: false })
+    }
+
+    fn update(
+        attack: &mut Self,
+        attack_start: Tick,
+        tower: &Tower<Self>,
+        enemies: &mut [Enemy],
+        index: &SpatialIndex,
+        _: &mut Vec<Projectile>,
+        tick: Tick,
+    ) -> bool {
+        let model = tower.get_model();
+        if (attack_start + tick::from_millis(model.get_attack_duration())) < tick {
+            return false;
+        }
+
+        if !attack.did_attack
+            && (attack_start + tick::from_millis(model.get_attack_damage_delay())) < tick
+        {
+            for i in tower.enemies_in_range(enemies, index) {
+                tower.hit(&mut enemies[i], model.get_attack());
+            }
+            attack.did_attack = true;
+        }
+
+        true
+    }
+}
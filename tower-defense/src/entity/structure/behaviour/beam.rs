@@ -0,0 +1,109 @@
+use crate::entity::structure::tower::{AttackBehaviour, Tower};
+use crate::entity::{Damage, Enemy, Projectile, Structure};
+use crate::math::SpatialIndex;
+use crate::tick::{self, Tick};
+use serde::{Deserialize, Serialize};
+
+/// Locks onto the targeted enemy and damages it continuously for the duration of the attack,
+/// starting `attack_damage_delay` after the attack started. The attack damage is dealt per
+/// second. The beam breaks off early if the target dies or leaves the range.
+#[derive(Serialize, Deserialize)]
+pub struct BeamAttack {
+    /// Id of the targeted enemy.
+    target: usize,
+    /// Status effects are only applied when the beam first hits.
+    did_hit: bool,
+}
+
+impl AttackBehaviour for BeamAttack {
+    fn start(
+        tower: &Tower<Self>,
+        enemies: &[Enemy],
+        index: &SpatialIndex,
+        _: Tick,
+    ) -> Option<Self> {
+        let target = tower.select_target(enemies, index)?;
+        Some(Self {
+            target: target.get_id(),
+            did_hit: false,
+        })
+    }
+
+    fn update(
+        attack: &mut Self,
+        attack_start: Tick,
+        tower: &Tower<Self>,
+        enemies: &mut [Enemy],
+        _: &SpatialIndex,
+        _: &mut Vec<Projectile>,
+        tick: Tick,
+    ) -> bool {
+        let model = tower.get_model();
+        if (attack_start + tick::from_millis(model.get_attack_duration())) < tick {
+            return false;
+        }
+        let target = match enemies
+            .iter_mut()
+            .find(|enemy| enemy.get_id() == attack.target)
+        {
+            Some(target) if tower.is_in_range(target) => target,
+            _ => return false,
+        };
+
+        if (attack_start + tick::from_millis(model.get_attack_damage_delay())) < tick {
+            let damage = Damage::new(
+                model.get_attack_damage() * tick::DELTA_TIME,
+                model.get_damage_type(),
+            );
+            if attack.did_hit {
+                target.apply_damage(damage, tower.get_damage_source());
+            } else {
+                tower.hit(target, damage);
+                attack.did_hit = true;
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod beam_tests {
+    use crate::entity::{EnemyType, StructureType};
+    use crate::math::{SpatialIndex, Vector2};
+    use crate::tick;
+
+    #[test]
+    fn damages_target_continuously() {
+        let structure = StructureType::try_from(String::from("LightningTowerBeam")).unwrap();
+        let model = structure.get_model();
+        let mut tower = structure.create(0, Vector2::new(100.0, 150.0));
+        let enemy_type = EnemyType::new_unchecked("PurpleKing");
+        let max_health = enemy_type.get_model().get_max_health();
+        let mut enemies = vec![enemy_type.create(1, String::from("main"), 0).with_progress(
+            0.0,
+            0.0,
+            tower.get_offset_position(),
+        )];
+        let mut index = SpatialIndex::new(1000.0, 1000.0, 100.0);
+        index.rebuild(enemies.iter().map(|enemy| enemy.get_position()));
+
+        let mut health = vec![];
+        for tick in 0..tick::from_millis(2500.0) {
+            tower.update(&mut enemies, &index, &mut vec![], tick);
+            health.push(enemies[0].get_health());
+        }
+
+        // The beam deals its damage per second every tick between the damage delay and the end
+        // of the attack. The boss resists a quarter of the electric damage.
+        let first = tick::from_millis(model.get_attack_damage_delay()) as usize + 1;
+        let last = tick::from_millis(model.get_attack_duration()) as usize;
+        assert_eq!(health[first - 1], max_health);
+        assert!(health[first] < max_health);
+        assert!(health[last] < health[last - 1]);
+        assert_eq!(health[last + 1], health[last]);
+        let per_tick = model.get_attack_damage() * tick::DELTA_TIME * 0.75;
+        let dealt = max_health - health[last];
+        assert!((dealt - per_tick * (last + 1 - first) as f64).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,7 @@
+mod area;
+mod beam;
+mod projectile;
+
+pub use area::AreaAttack;
+pub use beam::BeamAttack;
+pub use projectile::ProjectileAttack;
@@ -0,0 +1,59 @@
+use crate::entity::structure::tower::{AttackBehaviour, Tower};
+use crate::entity::{Enemy, Payload, Projectile, Structure};
+use crate::math::SpatialIndex;
+use crate::tick::{self, Tick};
+use serde::{Deserialize, Serialize};
+
+/// Fires a projectile at the targeted enemy `attack_damage_delay` after the attack started. The
+/// projectile flies on its own as described by the `projectile` entry of the model.
+#[derive(Serialize, Deserialize)]
+pub struct ProjectileAttack {
+    #[serde(default)]
+    did_fire: bool,
+}
+
+impl AttackBehaviour for ProjectileAttack {
+    fn start(
+        tower: &Tower<Self>,
+        enemies: &[Enemy],
+        index: &SpatialIndex,
+        _: Tick,
+    ) -> Option<Self> {
+        tower.select_target(enemies, index)?;
+        Some(Self { did_fire: false })
+    }
+
+    fn update(
+        attack: &mut Self,
+        attack_start: Tick,
+        tower: &Tower<Self>,
+        enemies: &mut [Enemy],
+        index: &SpatialIndex,
+        projectiles: &mut Vec<Projectile>,
+        tick: Tick,
+    ) -> bool {
+        let model = tower.get_model();
+        if (attack_start + tick::from_millis(model.get_attack_duration())) < tick {
+            return false;
+        }
+
+        if !attack.did_fire
+            && (attack_start + tick::from_millis(model.get_attack_damage_delay())) < tick
+        {
+            // The target may have left the range since the attack started.
+            let target = match tower.select_target(enemies, index) {
+                Some(target) => target,
+                None => return false,
+            };
+            projectiles.push(Projectile::fire(
+                tower.get_damage_source(),
+                tower.get_offset_position(),
+                target,
+                Payload::new(model),
+            ));
+            attack.did_fire = true;
+        }
+
+        true
+    }
+}
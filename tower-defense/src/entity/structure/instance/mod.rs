@@ -1,14 +0,0 @@
-mod konfetti_kanone_v1;
-mod konfetti_kanone_v2;
-
-mod lightning_tower;
-mod lightning_tower_v1;
-mod single_shot_v1;
-
-pub use lightning_tower::{LightningTower, LIGHTNING_TOWER_MODEL};
-pub use lightning_tower_v1::{LightningTowerV1, LIGHTNING_TOWER_V1_MODEL};
-
-pub use konfetti_kanone_v1::{KonfettiKanoneV1, KONFETTI_KANONE_MODEL};
-pub use konfetti_kanone_v2::{KonfettiKanoneV2, KONFETTI_KANONE_MODEL_V2};
-
-pub use single_shot_v1::{SingleShotTowerV1, SINGLE_SHOT_TOWER_V1_MODEL};
@@ -1,5 +1,18 @@
-mod instance;
+mod behaviour;
+mod model;
+mod ownership;
+#[allow(clippy::module_inception)]
 mod structure;
+mod targeting;
+mod tower;
 
-pub use instance::*;
+pub use behaviour::{AreaAttack, BeamAttack, ProjectileAttack};
+pub use model::{AttackKind, StructureModel, UpgradeNode};
+pub use ownership::ControlPolicy;
 pub use structure::{GameStructure, Structure, StructureType, STRUCTURE_MODEL_MAP};
+pub use targeting::{Reach, TargetingMode};
+pub use tower::{AttackBehaviour, Tower};
+
+pub type AreaTower = Tower<AreaAttack>;
+pub type ProjectileTower = Tower<ProjectileAttack>;
+pub type BeamTower = Tower<BeamAttack>;
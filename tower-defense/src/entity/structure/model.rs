@@ -0,0 +1,311 @@
+use crate::entity::gif::GifFrames;
+use crate::entity::{Damage, DamageType, EffectDefinition, ProjectileModel, Reach, StructureType};
+use crate::resources::{load_gif_frames, read_json_dir, ResourceError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+pub type StructureModelMap = BTreeMap<String, StructureModel>;
+
+/// Selects which implementation drives a tower.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+pub enum AttackKind {
+    /// Damages every enemy in range at once.
+    Area,
+    /// Fires a projectile at an enemy in range chosen by the targeting mode of the tower.
+    Projectile,
+    /// Damages an enemy in range chosen by the targeting mode continuously, the attack damage
+    /// being dealt per second.
+    Beam,
+}
+
+/****************************************
+* Upgrade Tree
+*****************************************/
+
+/// A structure a model can be upgraded into, along with everything it can be upgraded into in
+/// turn. Sent to the clients so that they do not need to know the upgrade graph.
+#[derive(Serialize, Clone, Debug)]
+pub struct UpgradeNode {
+    id: String,
+    name: String,
+    cost: usize,
+    upgrades: Vec<UpgradeNode>,
+}
+
+impl UpgradeNode {
+    pub fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn get_cost(&self) -> usize {
+        self.cost
+    }
+
+    pub fn get_upgrades(&self) -> &[UpgradeNode] {
+        &self.upgrades
+    }
+}
+
+/****************************************
+* Structure Model
+*****************************************/
+
+/// The shared, immutable description of a tower. Models are read from the json files in
+/// `resources/structures` and serialized as is to the clients.
+#[derive(Serialize, Deserialize)]
+pub struct StructureModel {
+    id: String,
+    name: String,
+    level: i64,
+    cost: usize,
+    can_be_bought: bool,
+    #[serde(skip_deserializing)]
+    can_be_upgraded: bool,
+    /// Ids of the structures this one can be upgraded into. The player picks one of them.
+    #[serde(default)]
+    upgrades: Vec<String>,
+    #[serde(skip_deserializing)]
+    upgrade_tree: Vec<UpgradeNode>,
+    behaviour: AttackKind,
+
+    icon: String,
+    idle_spritesheet: String,
+    attack_spritesheet: String,
+    #[serde(skip_serializing)]
+    idle_frames_file: String,
+    #[serde(skip_serializing)]
+    attack_frames_file: String,
+    #[serde(skip_deserializing)]
+    idle_frames: GifFrames,
+    #[serde(skip_deserializing)]
+    attack_frames: GifFrames,
+
+    radius: f64,
+    y_offset: f64,
+    max_health: f64,
+    attack_range: f64,
+    attack_damage: f64,
+    #[serde(default)]
+    damage_type: DamageType,
+    /// Whether the tower hits ground enemies, flying enemies or both.
+    #[serde(default)]
+    reach: Reach,
+    attack_cooldown: f64,
+    attack_damage_delay: f64,
+    attack_duration: f64,
+    /// Status effects applied to every enemy the tower damages.
+    #[serde(default)]
+    effects: Vec<EffectDefinition>,
+    /// The projectiles fired by towers with the `Projectile` behaviour.
+    #[serde(default)]
+    projectile: ProjectileModel,
+}
+
+impl StructureModel {
+    pub fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn get_cost(&self) -> usize {
+        self.cost
+    }
+
+    pub fn get_upgrades(&self) -> Vec<StructureType> {
+        self.upgrades
+            .iter()
+            .map(|upgrade| StructureType::new_unchecked(upgrade.clone()))
+            .collect()
+    }
+
+    pub fn get_upgrade_tree(&self) -> &[UpgradeNode] {
+        &self.upgrade_tree
+    }
+
+    pub fn get_behaviour(&self) -> AttackKind {
+        self.behaviour
+    }
+
+    pub fn get_radius(&self) -> f64 {
+        self.radius
+    }
+
+    pub fn get_y_offset(&self) -> f64 {
+        self.y_offset
+    }
+
+    pub fn get_max_health(&self) -> f64 {
+        self.max_health
+    }
+
+    pub fn get_attack_range(&self) -> f64 {
+        self.attack_range
+    }
+
+    pub fn get_attack_damage(&self) -> f64 {
+        self.attack_damage
+    }
+
+    pub fn get_damage_type(&self) -> DamageType {
+        self.damage_type
+    }
+
+    /// The damage of a single attack.
+    pub fn get_attack(&self) -> Damage {
+        Damage::new(self.attack_damage, self.damage_type)
+    }
+
+    pub fn get_reach(&self) -> Reach {
+        self.reach
+    }
+
+    pub fn get_attack_cooldown(&self) -> f64 {
+        self.attack_cooldown
+    }
+
+    pub fn get_attack_damage_delay(&self) -> f64 {
+        self.attack_damage_delay
+    }
+
+    pub fn get_attack_duration(&self) -> f64 {
+        self.attack_duration
+    }
+
+    pub fn get_effects(&self) -> &[EffectDefinition] {
+        &self.effects
+    }
+
+    pub fn get_projectile(&self) -> &ProjectileModel {
+        &self.projectile
+    }
+}
+
+/****************************************
+* Loading
+*****************************************/
+
+/// Reads all structure definitions in `<resources>/structures` and the sprite frames they
+/// reference.
+pub fn load_structure_models(resources: &Path) -> Result<StructureModelMap, ResourceError> {
+    let dir = resources.join("structures");
+    let mut map = StructureModelMap::new();
+    for (file, mut model) in read_json_dir::<StructureModel>(&dir)? {
+        let path = dir.join(format!("{}.json", file));
+
+        // The attack damage delay is the time it takes from the animation start until the damage
+        // is applied. If it were longer than the entire attack duration the damage would
+        // never get applied.
+        if model.attack_damage_delay >= model.attack_duration {
+            return Err(ResourceError::new(
+                &path,
+                String::from("attack_damage_delay must be shorter than attack_duration"),
+            ));
+        }
+
+        model
+            .projectile
+            .validate()
+            .map_err(|message| ResourceError::new(&path, message))?;
+
+        model.idle_frames = load_gif_frames(resources, &model.idle_frames_file)?;
+        model.attack_frames = load_gif_frames(resources, &model.attack_frames_file)?;
+        model.can_be_upgraded = !model.upgrades.is_empty();
+
+        if map.contains_key(&model.id) {
+            return Err(ResourceError::new(
+                &path,
+                format!("Duplicate structure id {}", model.id),
+            ));
+        }
+        map.insert(model.id.clone(), model);
+    }
+
+    for model in map.values() {
+        for upgrade in &model.upgrades {
+            if !map.contains_key(upgrade) {
+                return Err(ResourceError::new(
+                    &dir,
+                    format!("Unknown upgrade {} of structure {}", upgrade, model.id),
+                ));
+            }
+        }
+    }
+
+    let mut trees = BTreeMap::new();
+    for id in map.keys() {
+        let tree = build_upgrade_tree(&map, id, &mut vec![])
+            .map_err(|message| ResourceError::new(&dir, message))?;
+        trees.insert(id.clone(), tree);
+    }
+    for (id, tree) in trees {
+        map.get_mut(&id).unwrap().upgrade_tree = tree;
+    }
+
+    Ok(map)
+}
+
+/// The upgrades of the structure `id`, failing if a structure can be upgraded into itself.
+/// `visited` holds the structures on the way to `id`.
+fn build_upgrade_tree(
+    map: &StructureModelMap,
+    id: &str,
+    visited: &mut Vec<String>,
+) -> Result<Vec<UpgradeNode>, String> {
+    if visited.iter().any(|visited| visited == id) {
+        return Err(format!("Structure {} can be upgraded into itself", id));
+    }
+    visited.push(String::from(id));
+
+    let mut nodes = vec![];
+    for upgrade in &map[id].upgrades {
+        let model = &map[upgrade];
+        nodes.push(UpgradeNode {
+            id: model.id.clone(),
+            name: model.name.clone(),
+            cost: model.cost,
+            upgrades: build_upgrade_tree(map, upgrade, visited)?,
+        });
+    }
+    visited.pop();
+
+    Ok(nodes)
+}
+
+#[cfg(test)]
+mod model_tests {
+    use crate::entity::structure::model::{load_structure_models, AttackKind};
+    use crate::resources::RESOURCES_DIR;
+    use std::path::Path;
+
+    #[test]
+    fn load_resources() {
+        let models =
+            load_structure_models(Path::new(RESOURCES_DIR)).unwrap_or_else(|e| panic!("{}", e));
+
+        let lightning = &models["LightningTowerV1"];
+        assert_eq!(lightning.get_behaviour(), AttackKind::Area);
+        assert_eq!(
+            lightning
+                .get_upgrades()
+                .iter()
+                .map(|upgrade| upgrade.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                String::from("LightningTower"),
+                String::from("LightningTowerBeam")
+            ]
+        );
+        assert_eq!(
+            models["LightningTowerBeam"].get_behaviour(),
+            AttackKind::Beam
+        );
+
+        let single_shot = &models["SingleShotTowerV1"];
+        assert_eq!(single_shot.get_behaviour(), AttackKind::Projectile);
+        let tree = single_shot.get_upgrade_tree();
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0].get_id(), "SingleShotTowerSniper");
+        assert_eq!(tree[0].get_cost(), 250);
+        assert!(tree[0].get_upgrades().is_empty());
+    }
+}
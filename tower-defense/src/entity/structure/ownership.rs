@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+/// Who may upgrade and sell a structure. Chosen by the host of a lobby before the game starts.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ControlPolicy {
+    /// Every player may upgrade and sell every structure.
+    #[default]
+    Shared,
+    /// Only the player who placed a structure may upgrade or sell it.
+    OwnerOnly,
+}
+
+impl ControlPolicy {
+    /// Whether `player` may change a structure owned by `owner`. Structures without an owner,
+    /// e.g. from games saved before ownership was tracked, can be changed by everyone.
+    pub fn allows(&self, owner: Option<&str>, player: &str) -> bool {
+        match (self, owner) {
+            (Self::Shared, _) | (Self::OwnerOnly, None) => true,
+            (Self::OwnerOnly, Some(owner)) => owner == player,
+        }
+    }
+}
@@ -1,23 +1,28 @@
-use crate::entity::structure::instance::{LIGHTNING_TOWER_MODEL, LIGHTNING_TOWER_V1_MODEL};
-use crate::entity::structure::LightningTower;
-use crate::entity::structure::LightningTowerV1;
+use crate::entity::structure::model::{
+    load_structure_models, AttackKind, StructureModel, StructureModelMap,
+};
 use crate::entity::{
-    Enemy, KonfettiKanoneV1, KonfettiKanoneV2, SingleShotTowerV1, KONFETTI_KANONE_MODEL,
-    KONFETTI_KANONE_MODEL_V2, SINGLE_SHOT_TOWER_V1_MODEL,
+    AreaTower, BeamTower, DamageSource, Enemy, Projectile, ProjectileTower, TargetingMode,
 };
-use crate::math::Vector2;
+use crate::math::{SpatialIndex, Vector2};
+use crate::resources::RESOURCES_DIR;
+use crate::tick::Tick;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::Value;
 use std::fmt;
 use std::fmt::Formatter;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use strum::IntoEnumIterator;
-use strum_macros::EnumIter;
-
-pub type StructureModelMap = HashMap<String, Box<dyn StructureModel + 'static>>;
+use std::path::Path;
 
 pub trait StructureUpdate {
-    fn update(&mut self, enemies: &mut Vec<Enemy>, time: f64);
+    /// Advances the structure by a tick. `index` contains the positions of `enemies`, projectiles
+    /// the structure fires are added to `projectiles`.
+    fn update(
+        &mut self,
+        enemies: &mut [Enemy],
+        index: &SpatialIndex,
+        projectiles: &mut Vec<Projectile>,
+        tick: Tick,
+    );
 }
 
 pub trait GameStructure:
@@ -37,7 +42,20 @@ pub trait Structure {
     fn get_offset_position(&self) -> Vector2;
     fn set_position(&mut self, pos: Vector2);
     fn get_radius(&self) -> &f64;
-    fn get_upgrade(&self) -> Option<StructureType>;
+    /// The structures this one can be upgraded into.
+    fn get_upgrades(&self) -> Vec<StructureType>;
+    fn get_targeting(&self) -> TargetingMode;
+    fn set_targeting(&mut self, mode: TargetingMode);
+    /// Coins spent on the structure, including the upgrades that led to it.
+    fn get_invested(&self) -> usize;
+    fn set_invested(&mut self, invested: usize);
+    /// Name of the player who placed the structure.
+    fn get_owner(&self) -> Option<&str>;
+    fn set_owner(&mut self, owner: Option<String>);
+    /// The structure along with its current owner, credited with the damage it deals.
+    fn get_damage_source(&self) -> DamageSource {
+        DamageSource::new(self.get_id(), self.get_owner().map(String::from))
+    }
 
     fn get_health(&self) -> f64;
     fn inflict_damage(&mut self, damage: f64);
@@ -54,18 +72,24 @@ pub struct StructureBase {
     pos: Vector2,
     health: f64,
     radius: f64,
+    #[serde(default)]
+    targeting: TargetingMode,
+    #[serde(default)]
+    invested: usize,
+    #[serde(default)]
+    owner: Option<String>,
 }
 
 impl StructureBase {
-    pub(crate) fn new(health: f64, pos: Vector2, radius: f64) -> Self {
-        static ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
-        let id = ID_COUNTER.fetch_add(1, Ordering::Relaxed);
-
+    pub(crate) fn new(id: usize, health: f64, pos: Vector2, radius: f64) -> Self {
         StructureBase {
             id,
             pos,
             health,
             radius,
+            targeting: TargetingMode::default(),
+            invested: 0,
+            owner: None,
         }
     }
 }
@@ -91,8 +115,32 @@ impl Structure for StructureBase {
         &self.radius
     }
 
-    fn get_upgrade(&self) -> Option<StructureType> {
-        None
+    fn get_upgrades(&self) -> Vec<StructureType> {
+        vec![]
+    }
+
+    fn get_targeting(&self) -> TargetingMode {
+        self.targeting
+    }
+
+    fn set_targeting(&mut self, mode: TargetingMode) {
+        self.targeting = mode;
+    }
+
+    fn get_invested(&self) -> usize {
+        self.invested
+    }
+
+    fn set_invested(&mut self, invested: usize) {
+        self.invested = invested;
+    }
+
+    fn get_owner(&self) -> Option<&str> {
+        self.owner.as_deref()
+    }
+
+    fn set_owner(&mut self, owner: Option<String>) {
+        self.owner = owner;
     }
 
     fn get_health(&self) -> f64 {
@@ -109,71 +157,66 @@ impl Structure for StructureBase {
 }
 
 /****************************************
-* Structure Factory
+* Structure Type
 *****************************************/
 
-pub trait StructureFactory {
-    fn new(pos: Vector2) -> Self;
-}
+/// Identifies a structure model by its id. Deserializing a structure type fails for ids that
+/// are not defined in the resources.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "String", into = "String")]
+pub struct StructureType(String);
 
-pub trait StructureModel: Sync + Send + erased_serde::Serialize {
-    fn get_cost(&self) -> usize;
-}
-
-pub trait RegisterStructureModel {
-    fn register_model(model_map: &mut StructureModelMap);
-}
+impl StructureType {
+    /// Only for ids that have already been validated against [`STRUCTURE_MODEL_MAP`].
+    pub(crate) fn new_unchecked(id: String) -> Self {
+        Self(id)
+    }
 
-serialize_trait_object!(StructureModel);
+    pub fn create(&self, id: usize, pos: Vector2) -> Box<dyn GameStructure> {
+        let model = self.get_model();
+        match model.get_behaviour() {
+            AttackKind::Area => Box::new(AreaTower::new(id, pos, model)),
+            AttackKind::Projectile => Box::new(ProjectileTower::new(id, pos, model)),
+            AttackKind::Beam => Box::new(BeamTower::new(id, pos, model)),
+        }
+    }
 
-/****************************************
-* Structure Type
-*****************************************/
+    /// Restores a structure of this type from a saved game.
+    pub fn load(&self, value: &Value) -> Result<Box<dyn GameStructure>, serde_json::Error> {
+        let model = self.get_model();
+        Ok(match model.get_behaviour() {
+            AttackKind::Area => Box::new(AreaTower::load(value, model)?),
+            AttackKind::Projectile => Box::new(ProjectileTower::load(value, model)?),
+            AttackKind::Beam => Box::new(BeamTower::load(value, model)?),
+        })
+    }
 
-#[derive(Serialize, Deserialize, Debug, EnumIter)]
-pub enum StructureType {
-    LightningTowerV1,
-    LightningTower,
-    KonfettiKanoneV1,
-    KonfettiKanoneV2,
-    SingleShotTowerV1,
+    pub fn get_model(&self) -> &'static StructureModel {
+        &STRUCTURE_MODEL_MAP[&self.0]
+    }
 }
 
-impl StructureType {
-    pub fn new(self, pos: Vector2) -> Box<dyn GameStructure> {
-        match self {
-            StructureType::LightningTowerV1 => Box::new(LightningTowerV1::new(pos)),
-            StructureType::LightningTower => Box::new(LightningTower::new(pos)),
-            StructureType::KonfettiKanoneV1 => Box::new(KonfettiKanoneV1::new(pos)),
-            StructureType::KonfettiKanoneV2 => Box::new(KonfettiKanoneV2::new(pos)),
-            StructureType::SingleShotTowerV1 => Box::new(SingleShotTowerV1::new(pos)),
-        }
-    }
+impl TryFrom<String> for StructureType {
+    type Error = String;
 
-    fn register_model(&self, model_map: &mut StructureModelMap) {
-        match self {
-            StructureType::LightningTowerV1 => LightningTowerV1::register_model(model_map),
-            StructureType::LightningTower => LightningTower::register_model(model_map),
-            StructureType::KonfettiKanoneV1 => KonfettiKanoneV1::register_model(model_map),
-            StructureType::KonfettiKanoneV2 => KonfettiKanoneV2::register_model(model_map),
-            StructureType::SingleShotTowerV1 => SingleShotTowerV1::register_model(model_map),
+    fn try_from(id: String) -> Result<Self, Self::Error> {
+        if STRUCTURE_MODEL_MAP.contains_key(&id) {
+            Ok(Self(id))
+        } else {
+            Err(format!("Unknown structure type: {}", id))
         }
     }
+}
 
-    pub fn get_model(&self) -> Box<&dyn StructureModel> {
-        match self {
-            StructureType::LightningTowerV1 => Box::new(&*LIGHTNING_TOWER_V1_MODEL),
-            StructureType::LightningTower => Box::new(&*LIGHTNING_TOWER_MODEL),
-            StructureType::KonfettiKanoneV1 => Box::new(&*KONFETTI_KANONE_MODEL),
-            StructureType::KonfettiKanoneV2 => Box::new(&*KONFETTI_KANONE_MODEL_V2),
-            StructureType::SingleShotTowerV1 => Box::new(&*SINGLE_SHOT_TOWER_V1_MODEL),
-        }
+impl From<StructureType> for String {
+    fn from(structure_type: StructureType) -> Self {
+        structure_type.0
     }
 }
 
 impl fmt::Display for StructureType {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self)
+        write!(f, "{}", self.0)
     }
 }
 
@@ -182,12 +225,7 @@ impl fmt::Display for StructureType {
 *****************************************/
 
 lazy_static! {
-    pub static ref STRUCTURE_MODEL_MAP: StructureModelMap = {
-        let mut map: StructureModelMap = HashMap::new();
-        for structure_type in StructureType::iter() {
-            structure_type.register_model(&mut map);
-        }
-
-        map
-    };
+    pub static ref STRUCTURE_MODEL_MAP: StructureModelMap =
+        load_structure_models(Path::new(RESOURCES_DIR))
+            .unwrap_or_else(|e| panic!("Could not load structure models: {}", e));
 }
@@ -0,0 +1,133 @@
+use crate::entity::{Enemy, Movement};
+use crate::math::{SpatialIndex, Vector2};
+use serde::{Deserialize, Serialize};
+
+/// Decides which enemy in range a tower attacks. Players can change the mode of every tower.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum TargetingMode {
+    /// The enemy with the shortest way left to the end of its route.
+    #[default]
+    First,
+    /// The enemy with the longest way left to the end of its route.
+    Last,
+    /// The enemy with the most health left.
+    Strongest,
+    /// The enemy with the least health left.
+    Weakest,
+    /// The enemy closest to the tower.
+    Closest,
+    /// The enemy that currently moves the fastest.
+    Fastest,
+}
+
+/// Which enemies a tower can hit. Part of the structure model.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Reach {
+    Ground,
+    Air,
+    #[default]
+    Both,
+}
+
+impl Reach {
+    /// Whether `enemy` is alive and moves in a way the tower can hit.
+    pub fn can_hit(&self, enemy: &Enemy) -> bool {
+        enemy.is_alive()
+            && matches!(
+                (self, enemy.get_movement()),
+                (Self::Both, _) | (Self::Ground, Movement::Ground) | (Self::Air, Movement::Flying)
+            )
+    }
+}
+
+impl TargetingMode {
+    /// Picks the target among the living enemies within `range` of `origin` that are within
+    /// `reach`. `index` has to contain the positions of `enemies`.
+    pub fn select<'a>(
+        &self,
+        origin: &Vector2,
+        range: f64,
+        reach: Reach,
+        enemies: &'a [Enemy],
+        index: &SpatialIndex,
+    ) -> Option<&'a Enemy> {
+        if *self == Self::Closest {
+            return index
+                .nearest(origin, range, |i| reach.can_hit(&enemies[i]))
+                .map(|i| &enemies[i]);
+        }
+        let distance = |enemy: &Enemy| (origin - enemy.get_position()).magnitude();
+        let in_range = index
+            .within(origin, range)
+            .into_iter()
+            .map(|i| &enemies[i])
+            .filter(|enemy| reach.can_hit(enemy));
+
+        // Every mode is expressed as the enemy with the highest score.
+        let score = |enemy: &Enemy| match self {
+            Self::First => -enemy.get_remaining_distance(),
+            Self::Last => enemy.get_remaining_distance(),
+            Self::Strongest => enemy.get_health(),
+            Self::Weakest => -enemy.get_health(),
+            Self::Closest => -distance(enemy),
+            Self::Fastest => enemy.get_speed(),
+        };
+        in_range.max_by(|a, b| score(a).total_cmp(&score(b)))
+    }
+}
+
+#[cfg(test)]
+mod targeting_tests {
+    use crate::entity::{EnemyType, Reach, TargetingMode};
+    use crate::map::levels::{get_default_level, LEVEL_REGISTRY};
+    use crate::math::{SpatialIndex, Vector2};
+
+    #[test]
+    fn select() {
+        let map = &LEVEL_REGISTRY[get_default_level()];
+        let mut enemies = vec![
+            EnemyType::new_unchecked("Blue").create(0, String::from("main"), 0),
+            EnemyType::new_unchecked("Purple").create(1, String::from("main"), 0),
+            EnemyType::new_unchecked("Red").create(2, String::from("main"), 0),
+        ];
+        // Red enemies fly straight to the end of the path, so the least distance is left for
+        // them even though they moved the least.
+        for (enemy, distance) in enemies.iter_mut().zip([20.0, 10.0, 12.0]) {
+            enemy.move_by(distance);
+            enemy.update(0, map);
+        }
+        let mut index = SpatialIndex::new(1000.0, 1000.0, 100.0);
+        index.rebuild(enemies.iter().map(|enemy| enemy.get_position()));
+        let origin = Vector2::new(30.0, 180.0);
+        let select = |mode: TargetingMode| {
+            mode.select(&origin, 100.0, Reach::Both, &enemies, &index)
+                .unwrap()
+                .get_id()
+        };
+
+        assert_eq!(select(TargetingMode::First), 2);
+        assert_eq!(select(TargetingMode::Last), 1);
+        assert_eq!(select(TargetingMode::Strongest), 1);
+        assert_eq!(select(TargetingMode::Weakest), 2);
+        assert_eq!(select(TargetingMode::Closest), 0);
+        assert_eq!(select(TargetingMode::Fastest), 2);
+        assert!(TargetingMode::First
+            .select(
+                &Vector2::new(500.0, 0.0),
+                100.0,
+                Reach::Both,
+                &enemies,
+                &index
+            )
+            .is_none());
+        // Red enemies fly.
+        let select = |reach: Reach| {
+            TargetingMode::First
+                .select(&origin, 100.0, reach, &enemies, &index)
+                .unwrap()
+                .get_id()
+        };
+        assert_eq!(select(Reach::Ground), 0);
+        assert_eq!(select(Reach::Air), 2);
+    }
+}
@@ -0,0 +1,334 @@
+use crate::entity::structure::model::StructureModel;
+use crate::entity::structure::structure::{StructureBase, StructureUpdate};
+use crate::entity::{
+    Damage, Enemy, GameStructure, Projectile, Structure, StructureType, TargetingMode,
+};
+use crate::math::{SpatialIndex, Vector2};
+use crate::tick::{self, Tick};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value;
+
+/****************************************
+* Attack Behaviour
+*****************************************/
+
+/// What a tower does while it attacks. The shared state machine of [`Tower`] takes care of
+/// waiting for targets and of the cooldown between attacks. A value of the behaviour holds the
+/// data it keeps for the duration of an attack and is saved as part of the attack state.
+pub trait AttackBehaviour: Serialize + DeserializeOwned + Sized + Send + Sync + 'static {
+    /// Starts an attack if there is something to attack. `index` contains the positions of
+    /// `enemies`.
+    fn start(
+        tower: &Tower<Self>,
+        enemies: &[Enemy],
+        index: &SpatialIndex,
+        tick: Tick,
+    ) -> Option<Self>;
+
+    /// Advances a running attack by a tick. Projectiles fired by the tower are added to
+    /// `projectiles`. Returns `false` once the attack is over.
+    fn update(
+        attack: &mut Self,
+        attack_start: Tick,
+        tower: &Tower<Self>,
+        enemies: &mut [Enemy],
+        index: &SpatialIndex,
+        projectiles: &mut Vec<Projectile>,
+        tick: Tick,
+    ) -> bool;
+}
+
+/****************************************
+* States
+*****************************************/
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+#[serde(bound = "A: Serialize + DeserializeOwned")]
+enum State<A> {
+    Idle,
+    Attack {
+        attack_start: Tick,
+        #[serde(flatten)]
+        attack: A,
+    },
+    Cooldown {
+        attack_end: Tick,
+    },
+}
+
+impl<B: AttackBehaviour> State<B> {
+    fn update(
+        self,
+        enemies: &mut [Enemy],
+        index: &SpatialIndex,
+        projectiles: &mut Vec<Projectile>,
+        tick: Tick,
+        tower: &Tower<B>,
+    ) -> Self {
+        match self {
+            Self::Idle => match B::start(tower, enemies, index, tick) {
+                Some(attack) => Self::Attack {
+                    attack_start: tick,
+                    attack,
+                },
+                None => Self::Idle,
+            },
+            Self::Attack {
+                attack_start,
+                mut attack,
+            } => {
+                if B::update(
+                    &mut attack,
+                    attack_start,
+                    tower,
+                    enemies,
+                    index,
+                    projectiles,
+                    tick,
+                ) {
+                    Self::Attack {
+                        attack_start,
+                        attack,
+                    }
+                } else {
+                    Self::Cooldown { attack_end: tick }
+                }
+            }
+            Self::Cooldown { attack_end } => {
+                if (attack_end + tick::from_millis(tower.model.get_attack_cooldown())) < tick {
+                    Self::Idle
+                } else {
+                    self
+                }
+            }
+        }
+    }
+}
+
+/****************************************
+* Tower
+*****************************************/
+
+/// A tower driven by the attack behaviour `B`. Everything else about the tower is described by
+/// its model.
+#[derive(Serialize)]
+#[serde(bound = "")]
+pub struct Tower<B: AttackBehaviour> {
+    #[serde(flatten)]
+    base: StructureBase,
+    #[serde(serialize_with = "model_serialize")]
+    model: &'static StructureModel,
+    state: Option<State<B>>,
+}
+
+impl<B: AttackBehaviour> Tower<B> {
+    pub fn new(id: usize, pos: Vector2, model: &'static StructureModel) -> Self {
+        let mut base = StructureBase::new(id, model.get_max_health(), pos, model.get_radius());
+        base.set_invested(model.get_cost());
+        Self {
+            base,
+            model,
+            state: Some(State::Idle),
+        }
+    }
+
+    pub fn load(value: &Value, model: &'static StructureModel) -> Result<Self, serde_json::Error> {
+        let mut base: StructureBase = serde_json::from_value(value.clone())?;
+        // Saves from before the investment was tracked only know the cost of the current model.
+        if base.get_invested() == 0 {
+            base.set_invested(model.get_cost());
+        }
+        let state: State<B> = serde_json::from_value(value["state"].clone())?;
+        Ok(Self {
+            base,
+            model,
+            state: Some(state),
+        })
+    }
+
+    pub fn get_model(&self) -> &'static StructureModel {
+        self.model
+    }
+
+    /// Whether `enemy` is alive, within the reach of the tower and within the attack range.
+    pub fn is_in_range(&self, enemy: &Enemy) -> bool {
+        self.model.get_reach().can_hit(enemy)
+            && (&self.get_offset_position() - enemy.get_position()).magnitude()
+                < self.model.get_attack_range()
+    }
+
+    /// Indices of the living enemies within the reach of the tower and the attack range.
+    pub fn enemies_in_range(&self, enemies: &[Enemy], index: &SpatialIndex) -> Vec<usize> {
+        let reach = self.model.get_reach();
+        let mut in_range = index.within(&self.get_offset_position(), self.model.get_attack_range());
+        in_range.retain(|&i| reach.can_hit(&enemies[i]));
+        in_range
+    }
+
+    /// The enemy in range chosen by the targeting mode of the tower.
+    pub fn select_target<'a>(
+        &self,
+        enemies: &'a [Enemy],
+        index: &SpatialIndex,
+    ) -> Option<&'a Enemy> {
+        self.get_targeting().select(
+            &self.get_offset_position(),
+            self.model.get_attack_range(),
+            self.model.get_reach(),
+            enemies,
+            index,
+        )
+    }
+
+    /// Deals `damage` to `enemy` and applies the status effects of the tower to it.
+    pub fn hit(&self, enemy: &mut Enemy, damage: Damage) -> f64 {
+        let dealt = enemy.apply_damage(damage, self.get_damage_source());
+        for effect in self.model.get_effects() {
+            enemy.apply_effect(effect.create(self.get_damage_source()));
+        }
+
+        dealt
+    }
+}
+
+impl<B: AttackBehaviour> Structure for Tower<B> {
+    fn get_id(&self) -> usize {
+        self.base.get_id()
+    }
+
+    fn get_position(&self) -> &Vector2 {
+        self.base.get_position()
+    }
+
+    fn get_offset_position(&self) -> Vector2 {
+        let pos = self.base.get_position();
+        Vector2::new(pos.x(), pos.y() - self.model.get_y_offset())
+    }
+
+    fn set_position(&mut self, pos: Vector2) {
+        self.base.set_position(pos)
+    }
+
+    fn get_radius(&self) -> &f64 {
+        self.base.get_radius()
+    }
+
+    fn get_upgrades(&self) -> Vec<StructureType> {
+        self.model.get_upgrades()
+    }
+
+    fn get_targeting(&self) -> TargetingMode {
+        self.base.get_targeting()
+    }
+
+    fn set_targeting(&mut self, mode: TargetingMode) {
+        self.base.set_targeting(mode)
+    }
+
+    fn get_invested(&self) -> usize {
+        self.base.get_invested()
+    }
+
+    fn set_invested(&mut self, invested: usize) {
+        self.base.set_invested(invested)
+    }
+
+    fn get_owner(&self) -> Option<&str> {
+        self.base.get_owner()
+    }
+
+    fn set_owner(&mut self, owner: Option<String>) {
+        self.base.set_owner(owner)
+    }
+
+    fn get_health(&self) -> f64 {
+        self.base.get_health()
+    }
+
+    fn inflict_damage(&mut self, damage: f64) {
+        self.base.inflict_damage(damage)
+    }
+
+    fn heal(&mut self, amount: f64) {
+        self.base.heal(amount)
+    }
+}
+
+impl<B: AttackBehaviour> StructureUpdate for Tower<B> {
+    fn update(
+        &mut self,
+        enemies: &mut [Enemy],
+        index: &SpatialIndex,
+        projectiles: &mut Vec<Projectile>,
+        tick: Tick,
+    ) {
+        if let Some(s) = self.state.take() {
+            self.state = Some(s.update(enemies, index, projectiles, tick, self));
+        }
+    }
+}
+
+impl<B: AttackBehaviour> GameStructure for Tower<B> {}
+
+fn model_serialize<S>(model: &StructureModel, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    s.serialize_str(model.get_id())
+}
+
+#[cfg(test)]
+mod tower_tests {
+    use crate::entity::StructureType;
+    use serde_json::json;
+
+    /// Structures saved before the towers shared a state machine still load. Projectiles that
+    /// were in flight back then belonged to the tower, they are fired again as game projectiles.
+    #[test]
+    fn load_saved_state() {
+        let saved = [
+            json!({
+                "id": 3,
+                "pos": { "x": 100.0, "y": 100.0 },
+                "health": 100.0,
+                "radius": 50.0,
+                "model": "LightningTowerV1",
+                "state": { "type": "Attack", "data": { "attack_start": 12, "did_attack": true } }
+            }),
+            json!({
+                "id": 4,
+                "pos": { "x": 300.0, "y": 100.0 },
+                "health": 100.0,
+                "radius": 50.0,
+                "model": "SingleShotTowerV1",
+                "state": {
+                    "type": "Attack",
+                    "data": {
+                        "attack_start": 12,
+                        "projectile_pos_x": 1.0,
+                        "projectile_pos_y": 2.0,
+                        "target_x": 3.0,
+                        "target_y": 4.0
+                    }
+                }
+            }),
+        ];
+
+        for value in saved {
+            let structure_type =
+                StructureType::try_from(String::from(value["model"].as_str().unwrap())).unwrap();
+            let structure = structure_type.load(&value).unwrap();
+            assert_eq!(structure.get_id(), value["id"].as_u64().unwrap() as usize);
+
+            let serialized = serde_json::to_value(&structure).unwrap();
+            assert_eq!(serialized["model"], value["model"]);
+            assert_eq!(serialized["state"]["type"], "Attack");
+            assert_eq!(
+                serialized["state"]["data"]["attack_start"],
+                value["state"]["data"]["attack_start"]
+            );
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use crate::entity::EnemyType;
+use serde::Serialize;
+
+/// Something that happened in the game that clients can react to, e.g. with an announcement.
+/// Collected by the game until they are taken with [`crate::Game::take_events`].
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", content = "data")]
+pub enum GameEvent {
+    BossSpawned {
+        enemy: usize,
+        enemy_type: EnemyType,
+    },
+    /// The boss entered the phase with the given number, starting at 1.
+    BossPhaseChanged {
+        enemy: usize,
+        enemy_type: EnemyType,
+        phase: usize,
+    },
+    BossDefeated {
+        enemy: usize,
+        enemy_type: EnemyType,
+        /// Id of the structure that dealt the killing blow.
+        killed_by: Option<usize>,
+    },
+}
@@ -1,8 +1,26 @@
-use crate::entity::{Enemy, GameStructure, StructureType};
-use crate::map::Map;
-use crate::map::Wave;
-use crate::math::Vector2;
-use serde::{Deserialize, Serialize};
+use crate::entity::{
+    AbilityEvent, ControlPolicy, Enemy, EnemyType, GameStructure, Projectile, StructureType,
+    TargetingMode,
+};
+use crate::event::GameEvent;
+use crate::income::{Income, IncomeReason, Kill};
+use crate::map::{Map, PlacementError};
+use crate::map::{WaveProgress, WaveState};
+use crate::math::{SpatialIndex, Vector2};
+use crate::tick::{self, Tick};
+use log::warn;
+use rand::SeedableRng;
+use rand_pcg::Pcg32;
+use serde::{Deserialize, Serialize, Serializer};
+use std::sync::Arc;
+
+/// Upper limit of ticks simulated in a single call to [`Game::update`]. If the caller falls
+/// further behind, the remaining time is dropped instead of stalling the server even more.
+const MAX_TICKS_PER_UPDATE: u64 = 10;
+
+/// Cell size of the index used to find enemies in range. About the attack range of the smaller
+/// towers.
+const ENEMY_INDEX_CELL_SIZE: f64 = 100.0;
 
 #[derive(Debug, Clone)]
 pub struct GameError {
@@ -21,153 +39,525 @@ impl GameError {
 
 #[derive(Deserialize)]
 pub struct GameLoad {
-    time: f64,
+    map: String,
+    #[serde(default)]
+    seed: u64,
+    /// Missing in saves from before the seeded RNG, those continue with a generator seeded
+    /// from `seed`.
+    #[serde(default)]
+    rng: Option<Pcg32>,
+    /// Missing in saves from before the game handed out ids, those continue after the highest
+    /// id in use.
+    #[serde(default)]
+    next_id: Option<usize>,
+    tick: Tick,
     enemies: Vec<Enemy>,
+    #[serde(default)]
+    projectiles: Vec<Projectile>,
     current_lives: u64,
+    wave: WaveProgress,
+    #[serde(default)]
+    lives_lost_in_wave: u64,
     is_game_over: bool,
+    #[serde(default)]
+    control: ControlPolicy,
+}
+
+impl GameLoad {
+    /// Id of the map the game was saved on.
+    pub fn get_map(&self) -> &str {
+        &self.map
+    }
+}
+
+/// A game as it is written to a save. The state sent to the clients every tick leaves out the
+/// seed and the generator, which are only needed to continue the game exactly.
+#[derive(Serialize)]
+pub struct GameSave<'a> {
+    #[serde(flatten)]
+    game: &'a Game,
+    seed: u64,
+    rng: &'a Pcg32,
 }
 
 #[derive(Serialize)]
 pub struct Game {
+    #[serde(serialize_with = "map_serialize")]
+    map: Arc<Map>,
     #[serde(skip_serializing)]
-    map: &'static Map,
-    time: f64,
+    seed: u64,
+    #[serde(skip_serializing)]
+    rng: Pcg32,
+    next_id: usize,
+    tick: Tick,
+    #[serde(skip_serializing)]
+    accumulated_time: u64,
     enemies: Vec<Enemy>,
+    /// Positions of `enemies`, rebuilt every tick before structures and projectiles look for
+    /// targets.
+    #[serde(skip_serializing)]
+    enemy_index: SpatialIndex,
+    projectiles: Vec<Projectile>,
     structures: Vec<Box<dyn GameStructure>>,
     current_lives: u64,
-
+    wave: WaveProgress,
+    /// Lives lost since the last wave was cleared.
+    lives_lost_in_wave: u64,
+    control: ControlPolicy,
+    /// Events since they were last taken, sent to the clients separately from the state.
     #[serde(skip_serializing)]
-    wave: Wave,
+    events: Vec<GameEvent>,
 
     is_game_over: bool,
 }
 
 impl Game {
-    pub fn new(map: &'static Map) -> Game {
+    pub fn new(map: Arc<Map>, seed: u64) -> Game {
+        let current_lives = map.get_max_lives();
+        let wave = WaveProgress::new(map.get_waves());
+        let enemy_index = enemy_index(&map);
         Game {
             map,
-            time: 0.0,
+            seed,
+            rng: Pcg32::seed_from_u64(seed),
+            next_id: 0,
+            tick: 0,
+            accumulated_time: 0,
             enemies: vec![],
+            enemy_index,
+            projectiles: vec![],
             structures: vec![],
-            current_lives: map.get_max_lives(),
-            wave: Wave::new(300.0, 1500.0),
+            current_lives,
+            wave,
+            lives_lost_in_wave: 0,
+            control: ControlPolicy::default(),
+            events: vec![],
             is_game_over: false,
         }
     }
 
-    pub fn load(
-        map: &'static Map,
-        game: GameLoad,
-        structures: Vec<Box<dyn GameStructure>>,
-    ) -> Self {
+    pub fn with_control(self, control: ControlPolicy) -> Self {
+        Self { control, ..self }
+    }
+
+    /// The game along with everything needed to continue it after loading, see [`GameLoad`].
+    pub fn save(&self) -> GameSave<'_> {
+        GameSave {
+            game: self,
+            seed: self.seed,
+            rng: &self.rng,
+        }
+    }
+
+    pub fn load(map: Arc<Map>, game: GameLoad, structures: Vec<Box<dyn GameStructure>>) -> Self {
+        let enemy_index = enemy_index(&map);
+        let rng = game.rng.unwrap_or_else(|| Pcg32::seed_from_u64(game.seed));
+        let next_id = game.next_id.unwrap_or_else(|| {
+            let enemies = game.enemies.iter().map(Enemy::get_id);
+            let projectiles = game.projectiles.iter().map(Projectile::get_id);
+            let structures = structures.iter().map(|structure| structure.get_id());
+            enemies
+                .chain(projectiles)
+                .chain(structures)
+                .max()
+                .map_or(0, |id| id + 1)
+        });
         Self {
             map,
-            time: game.time,
+            seed: game.seed,
+            rng,
+            next_id,
+            tick: game.tick,
+            accumulated_time: 0,
             enemies: game.enemies,
+            enemy_index,
+            projectiles: game.projectiles,
             structures,
             current_lives: game.current_lives,
-            wave: Wave::new(300.0, 1500.0),
+            wave: game.wave,
+            lives_lost_in_wave: game.lives_lost_in_wave,
+            control: game.control,
+            events: vec![],
             is_game_over: game.is_game_over,
         }
     }
 
     pub fn start(&mut self) {}
 
-    pub fn update(&mut self, delta_time: f64) -> usize {
+    /// Advances the game by `elapsed` milliseconds of real time. The simulation itself only
+    /// moves in fixed ticks, time that does not fill a whole tick is carried over to the next
+    /// call. Returns the income earned along the way, each with the reason it was paid for.
+    pub fn update(&mut self, elapsed: u64) -> Vec<Income> {
+        self.accumulated_time += elapsed;
+        let mut ticks = self.accumulated_time / tick::TICK_DURATION;
+        self.accumulated_time %= tick::TICK_DURATION;
+        if ticks > MAX_TICKS_PER_UPDATE {
+            warn!(
+                "Game is running {} ticks behind, skipping ahead",
+                ticks - MAX_TICKS_PER_UPDATE
+            );
+            ticks = MAX_TICKS_PER_UPDATE;
+        }
+
+        let mut income = vec![];
+        for _ in 0..ticks {
+            income.extend(self.step());
+        }
+        income
+    }
+
+    /// Simulates a single tick.
+    pub fn step(&mut self) -> Vec<Income> {
         if self.is_game_over {
-            return 0;
+            return vec![];
         }
-        self.time += delta_time;
+        self.tick += 1;
+        self.enemy_index
+            .rebuild(self.enemies.iter().map(|enemy| enemy.get_position()));
+        let mut fired = vec![];
         for structure in &mut self.structures {
-            structure.update(&mut self.enemies, self.time);
+            structure.update(&mut self.enemies, &self.enemy_index, &mut fired, self.tick);
+        }
+        for mut projectile in fired {
+            projectile.set_id(self.generate_id());
+            self.projectiles.push(projectile);
         }
-        if let Some(enemy) = self.wave.update(delta_time) {
-            let enemy = enemy.new(self.time);
-            self.enemies.push(enemy);
+        self.update_projectiles();
+        let was_clearing = *self.wave.get_state() == WaveState::Clearing;
+        let spawns = self.wave.update(
+            self.map.get_waves(),
+            self.tick,
+            self.enemies.is_empty(),
+            &mut self.rng,
+        );
+        let mut income = vec![];
+        if was_clearing && *self.wave.get_state() != WaveState::Clearing {
+            income.extend(self.wave_cleared());
+        }
+        for (enemy_type, path) in spawns {
+            let path = path.unwrap_or_else(|| String::from(self.map.get_default_path_name()));
+            self.spawn_enemy(enemy_type, path);
         }
         self.update_enemies();
-        let gold_earned = self.remove_dead_enemies();
+        income.extend(self.remove_dead_enemies());
         self.check_enemies_in_base();
-        gold_earned
+        income
     }
 
     pub fn get_map(&self) -> &Map {
         &self.map
     }
 
+    pub fn get_wave(&self) -> &WaveProgress {
+        &self.wave
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn get_tick(&self) -> Tick {
+        self.tick
+    }
+
+    pub fn get_control(&self) -> ControlPolicy {
+        self.control
+    }
+
+    /// Takes the events that happened since the last call.
+    pub fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Ids are handed out by the game instead of a global counter so that the same seed and
+    /// inputs always produce the same state, and loaded games continue where they left off.
+    fn generate_id(&mut self) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    /// Places a structure owned by the player `owner`.
     pub fn try_place_structure(
         &mut self,
         structure: StructureType,
         pos: Vector2,
-    ) -> Result<(), GameError> {
-        let new_structure = structure.new(pos);
+        owner: &str,
+    ) -> Result<(), PlacementError> {
+        let model = structure.get_model();
+        let center = Vector2::new(pos.x(), pos.y() - model.get_y_offset());
+        self.check_placement(&center, model.get_radius(), None)?;
+
+        let id = self.generate_id();
+        let mut structure = structure.create(id, pos);
+        structure.set_owner(Some(String::from(owner)));
+        self.structures.push(structure);
+        Ok(())
+    }
+
+    /// Checks whether a structure with the given footprint can be built on the map without
+    /// overlapping other structures, except for the structure `replaced`.
+    fn check_placement(
+        &self,
+        center: &Vector2,
+        radius: f64,
+        replaced: Option<usize>,
+    ) -> Result<(), PlacementError> {
+        self.map.check_placement(center, radius)?;
 
         for structure in &self.structures {
-            let distance = (&structure.get_offset_position()
-                - &new_structure.get_offset_position())
-                .magnitude();
-            if distance < structure.get_radius() + new_structure.get_radius() {
-                return Err(GameError::new(String::from("Area obstructed")));
+            if Some(structure.get_id()) == replaced {
+                continue;
+            }
+            let distance = (&structure.get_offset_position() - center).magnitude();
+            if distance < structure.get_radius() + radius {
+                return Err(PlacementError::Obstructed);
             }
         }
 
-        self.structures.push(new_structure);
         Ok(())
     }
 
-    pub fn upgrade_structure(&mut self, id: usize) -> Result<(), GameError> {
-        let mut new_structure = None;
-        let mut pos = Vector2::new(0.0, 0.0);
-        self.structures.retain(|structure| {
-            if id == structure.get_id() {
-                new_structure = structure.get_upgrade();
-                if let Some(_) = &new_structure {
-                    pos = structure.get_position().clone();
-                    return false;
-                }
+    /// Replaces a structure with `target`, which has to be one of its upgrades, on behalf of
+    /// `player`.
+    pub fn upgrade_structure(
+        &mut self,
+        id: usize,
+        target: StructureType,
+        player: &str,
+    ) -> Result<(), GameError> {
+        let index = self.controlled_structure(id, player)?;
+        let old = &self.structures[index];
+        if !old.get_upgrades().contains(&target) {
+            return Err(GameError::new(format!(
+                "Structure {} cannot be upgraded into {}",
+                id, target
+            )));
+        }
+
+        let pos = old.get_position().clone();
+        let model = target.get_model();
+        let center = Vector2::new(pos.x(), pos.y() - model.get_y_offset());
+        // An upgrade that stays within the footprint of the old structure always fits.
+        if (&center - &old.get_offset_position()).magnitude() + model.get_radius()
+            > *old.get_radius()
+        {
+            self.check_placement(&center, model.get_radius(), Some(id))
+                .map_err(|error| {
+                    GameError::new(format!(
+                        "Structure {} cannot be upgraded into {}: {}",
+                        id, target, error
+                    ))
+                })?;
+        }
+
+        let old = &self.structures[index];
+        let targeting = old.get_targeting();
+        let invested = old.get_invested();
+        let owner = old.get_owner().map(String::from);
+        let new_id = self.generate_id();
+        let mut structure = target.create(new_id, pos);
+        structure.set_targeting(targeting);
+        structure.set_invested(invested + structure.get_invested());
+        structure.set_owner(owner);
+        self.structures[index] = structure;
+
+        Ok(())
+    }
+
+    /// Removes a structure on behalf of `player` and returns the coins refunded for it.
+    pub fn sell_structure(&mut self, id: usize, player: &str) -> Result<usize, GameError> {
+        let index = self.controlled_structure(id, player)?;
+        let structure = self.structures.remove(index);
+        Ok(self.map.get_economy().refund(structure.get_invested()))
+    }
+
+    /// Hands every structure owned by `from` over to `to`, e.g. when a player leaves. Returns
+    /// the number of structures handed over.
+    pub fn transfer_structures(&mut self, from: &str, to: &str) -> usize {
+        let mut count = 0;
+        for structure in &mut self.structures {
+            if structure.get_owner() == Some(from) {
+                structure.set_owner(Some(String::from(to)));
+                count += 1;
             }
+        }
 
-            true
-        });
+        count
+    }
 
-        if let Some(structure) = new_structure {
-            self.structures.push(structure.new(pos));
-            return Ok(());
+    /// Index of the structure `id` if the control policy lets `player` change it.
+    fn controlled_structure(&self, id: usize, player: &str) -> Result<usize, GameError> {
+        let index = self
+            .structures
+            .iter()
+            .position(|x| x.get_id() == id)
+            .ok_or_else(|| GameError::new(format!("Unknown structure {}", id)))?;
+        if !self
+            .control
+            .allows(self.structures[index].get_owner(), player)
+        {
+            return Err(GameError::new(format!(
+                "{} does not control structure {}",
+                player, id
+            )));
         }
 
-        Err(GameError::new(String::from("Could not upgrade")))
+        Ok(index)
+    }
+
+    /// Adds an enemy at the start of `path` and announces it if it is a boss. Returns its id.
+    fn spawn_enemy(&mut self, enemy_type: EnemyType, path: String) -> usize {
+        let id = self.generate_id();
+        if enemy_type.get_model().is_boss() {
+            self.events.push(GameEvent::BossSpawned {
+                enemy: id,
+                enemy_type: enemy_type.clone(),
+            });
+        }
+        self.enemies.push(enemy_type.create(id, path, self.tick));
+        id
     }
 
-    fn remove_dead_enemies(&mut self) -> usize {
-        let mut gold_earned: usize = 0;
+    /// The bonuses for clearing the current wave. Always includes the wave clear bonus, even if
+    /// the map does not pay one, as interest is paid along with it.
+    fn wave_cleared(&mut self) -> Vec<Income> {
+        let rules = self.map.get_economy();
+        let wave = self.wave.get_wave();
+        let mut income = vec![Income::new(
+            rules.get_wave_clear_bonus(),
+            IncomeReason::WaveCleared { wave },
+        )];
+        if self.lives_lost_in_wave == 0 && rules.get_no_lives_lost_bonus() > 0 {
+            income.push(Income::new(
+                rules.get_no_lives_lost_bonus(),
+                IncomeReason::NoLivesLost { wave },
+            ));
+        }
+        self.lives_lost_in_wave = 0;
+
+        income
+    }
+
+    fn remove_dead_enemies(&mut self) -> Vec<Income> {
+        let events = &mut self.events;
+        let mut income = vec![];
         self.enemies.retain(|enemy| {
-            return if enemy.is_dead() {
-                let coins = enemy.get_enemy_type().get_model().get_coin_reward();
-                gold_earned += coins;
-                false
-            } else {
-                true
-            };
+            if !enemy.is_dead() {
+                return true;
+            }
+            let source = enemy.get_killed_by();
+            let structure = source.map(|source| source.get_structure());
+            if enemy.get_enemy_type().get_model().is_boss() {
+                events.push(GameEvent::BossDefeated {
+                    enemy: enemy.get_id(),
+                    enemy_type: enemy.get_enemy_type().clone(),
+                    killed_by: structure,
+                });
+            }
+            let owner = source.and_then(|source| source.get_owner().map(String::from));
+            let kill = Kill::new(
+                enemy.get_id(),
+                enemy.get_enemy_type().clone(),
+                structure,
+                owner,
+            );
+            let coins = enemy.get_enemy_type().get_model().get_coin_reward();
+            income.push(Income::new(coins, IncomeReason::Kill(kill)));
+            false
         });
 
-        gold_earned
+        income
+    }
+
+    fn update_projectiles(&mut self) {
+        let enemies = &mut self.enemies;
+        let index = &self.enemy_index;
+        self.projectiles
+            .retain_mut(|projectile| projectile.update(enemies, index));
     }
 
     fn update_enemies(&mut self) {
+        let mut events = vec![];
         for enemy in self.enemies.iter_mut() {
-            enemy.update(self.time, self.map);
+            events.extend(enemy.update(self.tick, &self.map));
+        }
+        // The enemies moved since the index was built at the start of the tick.
+        if events
+            .iter()
+            .any(|event| matches!(event, AbilityEvent::Heal { .. }))
+        {
+            self.enemy_index
+                .rebuild(self.enemies.iter().map(|enemy| enemy.get_position()));
+        }
+        for event in events {
+            match event {
+                AbilityEvent::Heal {
+                    source,
+                    center,
+                    radius,
+                    amount,
+                } => {
+                    for i in self.enemy_index.within(&center, radius) {
+                        let enemy = &mut self.enemies[i];
+                        if enemy.get_id() != source && enemy.is_alive() {
+                            enemy.heal(amount);
+                        }
+                    }
+                }
+                AbilityEvent::Spawn {
+                    into,
+                    count,
+                    path,
+                    movement,
+                    distance,
+                    pos,
+                } => {
+                    // Enemies that move differently take another route, they continue from the
+                    // point of it closest to where they were spawned.
+                    let child_movement = into.get_model().get_movement();
+                    let route = self.map.get_route(&path, child_movement);
+                    let distance = match route {
+                        Some(route) if child_movement != movement => route.distance_along(&pos),
+                        _ => distance,
+                    };
+                    let route_length = route.map(|route| route.length()).unwrap_or_default();
+                    for _ in 0..count {
+                        let id = self.generate_id();
+                        let enemy = into.create(id, path.clone(), self.tick).with_progress(
+                            distance,
+                            route_length,
+                            pos.clone(),
+                        );
+                        self.enemies.push(enemy);
+                    }
+                }
+                AbilityEvent::PhaseChanged { source, phase } => {
+                    if let Some(enemy) = self.enemies.iter().find(|x| x.get_id() == source) {
+                        self.events.push(GameEvent::BossPhaseChanged {
+                            enemy: source,
+                            enemy_type: enemy.get_enemy_type().clone(),
+                            phase,
+                        });
+                    }
+                }
+            }
         }
     }
 
     fn check_enemies_in_base(&mut self) {
-        let rect = self.map.get_base();
+        let bases = self.map.get_bases();
         self.enemies.retain(|enemy| {
-            if !enemy.is_alive() {
+            if self.is_game_over || !enemy.is_alive() {
                 return true;
             }
-            let is_inside = rect.is_inside(enemy.get_position());
+            let is_inside = bases
+                .iter()
+                .any(|base| base.is_inside(enemy.get_position()));
             if is_inside {
-                self.current_lives -= 1;
+                let damage = enemy.get_enemy_type().get_model().get_damage();
+                self.current_lives = self.current_lives.saturating_sub(damage);
+                self.lives_lost_in_wave += damage;
                 if self.current_lives == 0 {
                     self.is_game_over = true;
                 }
@@ -177,7 +567,313 @@ impl Game {
         });
     }
 
-    pub fn find_structure(&self, id: usize) -> Option<&Box<dyn GameStructure>> {
-        self.structures.iter().find(|x| x.get_id() == id)
+    pub fn find_structure(&self, id: usize) -> Option<&dyn GameStructure> {
+        self.structures
+            .iter()
+            .find(|x| x.get_id() == id)
+            .map(|x| x.as_ref())
+    }
+
+    pub fn set_targeting(&mut self, id: usize, mode: TargetingMode) -> Result<(), GameError> {
+        match self.structures.iter_mut().find(|x| x.get_id() == id) {
+            Some(structure) => {
+                structure.set_targeting(mode);
+                Ok(())
+            }
+            None => Err(GameError::new(format!("Unknown structure {}", id))),
+        }
+    }
+}
+
+fn enemy_index(map: &Map) -> SpatialIndex {
+    let size = map.get_size();
+    SpatialIndex::new(size.x() as f64, size.y() as f64, ENEMY_INDEX_CELL_SIZE)
+}
+
+fn map_serialize<S>(map: &Arc<Map>, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    s.serialize_str(map.get_id())
+}
+
+#[cfg(test)]
+mod game_tests {
+    use crate::entity::{ControlPolicy, Damage, DamageType, EnemyType, StructureType};
+    use crate::map::levels::LEVEL_REGISTRY;
+    use crate::math::Vector2;
+    use crate::{Game, GameEvent, IncomeReason};
+    use rand::{RngCore, SeedableRng};
+    use rand_pcg::Pcg32;
+
+    #[test]
+    fn save_and_broadcast() {
+        let map = LEVEL_REGISTRY.get("map_1").unwrap().clone();
+        let mut game = Game::new(map.clone(), 7);
+        game.rng.next_u32();
+
+        // Clients must not be able to predict the random numbers.
+        let state = serde_json::to_value(&game).unwrap();
+        assert!(state.get("seed").is_none());
+        assert!(state.get("rng").is_none());
+
+        let save = serde_json::to_value(game.save()).unwrap();
+        let loaded = Game::load(map, serde_json::from_value(save).unwrap(), vec![]);
+        assert_eq!(loaded.seed, 7);
+        assert!(loaded.rng == game.rng);
+    }
+
+    #[test]
+    fn load_save_without_rng() {
+        let map = LEVEL_REGISTRY.get("map_1").unwrap().clone();
+        let mut game = Game::new(map.clone(), 7);
+        game.enemies
+            .push(EnemyType::new_unchecked("Blue").create(100, String::from("main"), 0));
+        let mut save = serde_json::to_value(game.save()).unwrap();
+        for field in ["rng", "next_id"] {
+            save.as_object_mut().unwrap().remove(field);
+        }
+
+        let game = Game::load(map, serde_json::from_value(save).unwrap(), vec![]);
+        assert_eq!(game.next_id, 101);
+        assert!(game.rng == Pcg32::seed_from_u64(7));
+    }
+
+    #[test]
+    fn sell_structure() {
+        let map = LEVEL_REGISTRY.get("map_1").unwrap().clone();
+        let mut game = Game::new(map, 0);
+        let structure = StructureType::try_from(String::from("SingleShotTowerV1")).unwrap();
+        game.try_place_structure(structure, Vector2::new(1000.0, 450.0), "alice")
+            .unwrap();
+        let id = game.structures[0].get_id();
+        let lightning = StructureType::try_from(String::from("LightningTower")).unwrap();
+        assert!(game.upgrade_structure(id, lightning, "alice").is_err());
+        let sniper = StructureType::try_from(String::from("SingleShotTowerSniper")).unwrap();
+        game.upgrade_structure(id, sniper, "bob").unwrap();
+
+        // 150 for the tower and 250 for the upgrade.
+        let id = game.structures[0].get_id();
+        assert_eq!(game.find_structure(id).unwrap().get_invested(), 400);
+        assert_eq!(game.sell_structure(id, "alice").unwrap(), 300);
+        assert!(game.structures.is_empty());
+        assert!(game.sell_structure(id, "alice").is_err());
+    }
+
+    #[test]
+    fn owner_only_control() {
+        let map = LEVEL_REGISTRY.get("map_1").unwrap().clone();
+        let mut game = Game::new(map, 0).with_control(ControlPolicy::OwnerOnly);
+        let structure = StructureType::try_from(String::from("SingleShotTowerV1")).unwrap();
+        game.try_place_structure(structure, Vector2::new(1000.0, 450.0), "alice")
+            .unwrap();
+        let id = game.structures[0].get_id();
+
+        let sniper = StructureType::try_from(String::from("SingleShotTowerSniper")).unwrap();
+        assert!(game.upgrade_structure(id, sniper.clone(), "bob").is_err());
+        game.upgrade_structure(id, sniper, "alice").unwrap();
+        let id = game.structures[0].get_id();
+        assert_eq!(game.structures[0].get_owner(), Some("alice"));
+
+        assert_eq!(game.transfer_structures("alice", "bob"), 1);
+        assert!(game.sell_structure(id, "alice").is_err());
+        assert!(game.sell_structure(id, "bob").is_ok());
+    }
+
+    #[test]
+    fn wave_clear_bonuses() {
+        let map = LEVEL_REGISTRY.get("map_1").unwrap().clone();
+        let mut game = Game::new(map, 0);
+        let rules = game.map.get_economy().clone();
+
+        // Every enemy of the first wave dies right after it spawned, so no lives are lost. The
+        // second wave starts after 20 seconds at the earliest.
+        let mut income = vec![];
+        for _ in 0..800 {
+            for enemy in &mut game.enemies {
+                enemy.apply_damage(Damage::new(1000.0, DamageType::Fire), 0);
+            }
+            income.extend(game.step());
+        }
+        let bonuses: Vec<_> = income
+            .iter()
+            .filter(|income| !matches!(income.get_reason(), IncomeReason::Kill(_)))
+            .map(|income| (income.get_reason().clone(), income.get_amount()))
+            .collect();
+        assert!(matches!(
+            bonuses.as_slice(),
+            [
+                (IncomeReason::WaveCleared { wave: 1 }, clear),
+                (IncomeReason::NoLivesLost { wave: 1 }, no_lives_lost),
+            ] if *clear == rules.get_wave_clear_bonus()
+                && *no_lives_lost == rules.get_no_lives_lost_bonus()
+        ));
+    }
+
+    /// Projectiles in flight still pay their kill to the player who owned the tower that fired
+    /// them, even though the tower has been replaced.
+    #[test]
+    fn kill_after_upgrade_or_sale() {
+        let sniper = StructureType::try_from(String::from("SingleShotTowerSniper")).unwrap();
+        for sell in [false, true] {
+            let map = LEVEL_REGISTRY.get("map_1").unwrap().clone();
+            let mut game = Game::new(map, 0);
+            // Right next to the path, where towers cannot be placed regularly.
+            let tower = game.generate_id();
+            let mut structure = StructureType::try_from(String::from("SingleShotTowerV1"))
+                .unwrap()
+                .create(tower, Vector2::new(1000.0, 250.0));
+            structure.set_owner(Some(String::from("alice")));
+            game.structures.push(structure);
+            let distance = 1820.0;
+            let path = game.map.get_path("main").unwrap();
+            let (position, length) = (path.coords_at(distance), path.length());
+            game.enemies.push(
+                EnemyType::new_unchecked("Blue")
+                    .create(100, String::from("main"), 0)
+                    .with_progress(distance, length, position),
+            );
+
+            while game.projectiles.is_empty() {
+                game.step();
+            }
+            if sell {
+                game.sell_structure(tower, "alice").unwrap();
+            } else {
+                game.upgrade_structure(tower, sniper.clone(), "alice")
+                    .unwrap();
+            }
+            let kill = (0..100)
+                .flat_map(|_| game.step())
+                .find_map(|income| match income.get_reason() {
+                    IncomeReason::Kill(kill) if kill.get_enemy() == 100 => Some(kill.clone()),
+                    _ => None,
+                })
+                .unwrap();
+            assert_eq!(kill.get_structure(), Some(tower));
+            assert_eq!(kill.get_owner(), Some("alice"));
+        }
+    }
+
+    #[test]
+    fn kill_attribution() {
+        let map = LEVEL_REGISTRY.get("map_1").unwrap().clone();
+        let mut game = Game::new(map, 0);
+        let structure = StructureType::try_from(String::from("SingleShotTowerV1")).unwrap();
+        game.try_place_structure(structure, Vector2::new(1000.0, 450.0), "alice")
+            .unwrap();
+        let tower = game.structures[0].get_id();
+
+        let mut enemy = EnemyType::new_unchecked("Blue").create(100, String::from("main"), 0);
+        let source = game.structures[0].get_damage_source();
+        enemy.apply_damage(Damage::new(1000.0, DamageType::Fire), source);
+        game.enemies.push(enemy);
+
+        let kills: Vec<_> = (0..200)
+            .flat_map(|_| game.step())
+            .filter_map(|income| match income.get_reason() {
+                IncomeReason::Kill(kill) => Some(kill.clone()),
+                _ => None,
+            })
+            .collect();
+        let kill = kills.iter().find(|kill| kill.get_enemy() == 100).unwrap();
+        assert_eq!(kill.get_structure(), Some(tower));
+        assert_eq!(kill.get_owner(), Some("alice"));
+    }
+
+    #[test]
+    fn boss_fight() {
+        let map = LEVEL_REGISTRY.get("map_1").unwrap().clone();
+        let mut game = Game::new(map, 0);
+        let boss_type = EnemyType::new_unchecked("PurpleKing");
+        let boss = game.spawn_enemy(boss_type.clone(), String::from("main"));
+        let max_health = boss_type.get_model().get_max_health();
+
+        // Fire damage ignores the armor of the boss. Each hit takes it into the next phase, the
+        // last one kills it. The first wave starts after 3 seconds.
+        let thresholds: Vec<_> = boss_type
+            .get_model()
+            .get_phases()
+            .iter()
+            .map(|phase| phase.get_health() * max_health)
+            .collect();
+        game.step();
+        for health in thresholds.iter().chain([&0.0]) {
+            let enemy = game
+                .enemies
+                .iter_mut()
+                .find(|x| x.get_id() == boss)
+                .unwrap();
+            let damage = enemy.get_health() - health + 1.0;
+            enemy.apply_damage(Damage::new(damage, DamageType::Fire), 3);
+            game.step();
+        }
+        let minions: Vec<_> = game
+            .enemies
+            .iter()
+            .filter(|x| x.get_id() != boss)
+            .map(|x| x.get_enemy_type().get_model().get_id())
+            .collect();
+        assert_eq!(
+            minions,
+            vec!["Purple", "Purple", "Blue", "Blue", "Blue", "Blue"]
+        );
+        for _ in 0..50 {
+            game.step();
+        }
+
+        let events = game.take_events();
+        assert_eq!(
+            events,
+            vec![
+                GameEvent::BossSpawned {
+                    enemy: boss,
+                    enemy_type: boss_type.clone(),
+                },
+                GameEvent::BossPhaseChanged {
+                    enemy: boss,
+                    enemy_type: boss_type.clone(),
+                    phase: 1,
+                },
+                GameEvent::BossPhaseChanged {
+                    enemy: boss,
+                    enemy_type: boss_type.clone(),
+                    phase: 2,
+                },
+                GameEvent::BossDefeated {
+                    enemy: boss,
+                    enemy_type: boss_type,
+                    killed_by: Some(3),
+                },
+            ]
+        );
+        assert!(game.take_events().is_empty());
+    }
+
+    #[test]
+    fn split_on_death() {
+        let map = LEVEL_REGISTRY.get("map_1").unwrap().clone();
+        let mut game = Game::new(map, 0);
+        let path = game.map.get_path("main").unwrap();
+        let (position, length) = (path.coords_at(500.0), path.length());
+        let mut enemy = EnemyType::new_unchecked("Purple")
+            .create(100, String::from("main"), 0)
+            .with_progress(500.0, length, position);
+        enemy.apply_damage(Damage::new(1000.0, DamageType::Fire), 0);
+        game.enemies.push(enemy);
+        game.step();
+
+        let children: Vec<_> = game
+            .enemies
+            .iter()
+            .filter(|enemy| enemy.get_id() != 100)
+            .collect();
+        assert_eq!(children.len(), 2);
+        for child in children {
+            assert_eq!(child.get_enemy_type().get_model().get_id(), "Blue");
+            assert_eq!(child.get_distance(), 500.0);
+            // Targeted by the distance left before they move for the first time.
+            assert_eq!(child.get_remaining_distance(), length - 500.0);
+        }
     }
 }
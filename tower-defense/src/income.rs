@@ -0,0 +1,86 @@
+use crate::entity::EnemyType;
+use serde::Serialize;
+
+/// An enemy killed by the players.
+#[derive(Serialize, Clone, Debug)]
+pub struct Kill {
+    enemy: usize,
+    enemy_type: EnemyType,
+    /// Id of the structure that dealt the killing blow.
+    structure: Option<usize>,
+    /// The player who owned that structure when it dealt the killing blow.
+    owner: Option<String>,
+}
+
+impl Kill {
+    pub(crate) fn new(
+        enemy: usize,
+        enemy_type: EnemyType,
+        structure: Option<usize>,
+        owner: Option<String>,
+    ) -> Self {
+        Self {
+            enemy,
+            enemy_type,
+            structure,
+            owner,
+        }
+    }
+
+    pub fn get_enemy(&self) -> usize {
+        self.enemy
+    }
+
+    pub fn get_enemy_type(&self) -> &EnemyType {
+        &self.enemy_type
+    }
+
+    pub fn get_structure(&self) -> Option<usize> {
+        self.structure
+    }
+
+    pub fn get_owner(&self) -> Option<&str> {
+        self.owner.as_deref()
+    }
+}
+
+/// Why coins were earned.
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "type", content = "data")]
+pub enum IncomeReason {
+    Kill(Kill),
+    /// The bonus for clearing a wave.
+    WaveCleared {
+        wave: usize,
+    },
+    /// The bonus for clearing a wave without losing a life.
+    NoLivesLost {
+        wave: usize,
+    },
+    /// Interest on the coins that were not spent when a wave was cleared. Depends on the wallet
+    /// it is paid into, so it is not reported by the game itself.
+    Interest {
+        wave: usize,
+    },
+}
+
+/// Coins earned by the players.
+#[derive(Serialize, Clone, Debug)]
+pub struct Income {
+    amount: usize,
+    reason: IncomeReason,
+}
+
+impl Income {
+    pub fn new(amount: usize, reason: IncomeReason) -> Self {
+        Self { amount, reason }
+    }
+
+    pub fn get_amount(&self) -> usize {
+        self.amount
+    }
+
+    pub fn get_reason(&self) -> &IncomeReason {
+        &self.reason
+    }
+}
@@ -1,9 +1,15 @@
 pub mod entity;
+mod event;
 mod game;
+mod income;
 pub mod map;
 pub mod math;
+mod resources;
+pub mod tick;
 
-pub use game::{Game, GameLoad};
+pub use event::GameEvent;
+pub use game::{Game, GameLoad, GameSave};
+pub use income::{Income, IncomeReason, Kill};
 
 #[macro_use]
 extern crate lazy_static;
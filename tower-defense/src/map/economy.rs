@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+
+/// How coins are handed out on a map. All bonuses are off unless the map sets them.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EconomyRules {
+    /// Share of the coins spent on a structure, including its upgrades, that is refunded when
+    /// it is sold.
+    #[serde(default = "default_sell_refund")]
+    sell_refund: f64,
+    /// Coins paid for clearing a wave.
+    #[serde(default)]
+    wave_clear_bonus: usize,
+    /// Additional coins paid for clearing a wave without losing a life.
+    #[serde(default)]
+    no_lives_lost_bonus: usize,
+    /// Share of the unspent coins paid as interest whenever a wave is cleared.
+    #[serde(default)]
+    interest_rate: f64,
+    /// The most interest paid at once, unlimited if not set.
+    #[serde(default)]
+    interest_cap: Option<usize>,
+}
+
+fn default_sell_refund() -> f64 {
+    0.75
+}
+
+impl EconomyRules {
+    pub fn with_interest(self, interest_rate: f64, interest_cap: Option<usize>) -> Self {
+        Self {
+            interest_rate,
+            interest_cap,
+            ..self
+        }
+    }
+
+    pub fn get_sell_refund(&self) -> f64 {
+        self.sell_refund
+    }
+
+    pub fn get_wave_clear_bonus(&self) -> usize {
+        self.wave_clear_bonus
+    }
+
+    pub fn get_no_lives_lost_bonus(&self) -> usize {
+        self.no_lives_lost_bonus
+    }
+
+    pub fn get_interest_rate(&self) -> f64 {
+        self.interest_rate
+    }
+
+    /// Coins refunded for a structure that cost `invested` in total.
+    pub fn refund(&self, invested: usize) -> usize {
+        (invested as f64 * self.sell_refund).floor() as usize
+    }
+
+    /// Interest paid on `coins` unspent coins.
+    pub fn interest(&self, coins: usize) -> usize {
+        let interest = (coins as f64 * self.interest_rate).floor() as usize;
+        match self.interest_cap {
+            Some(cap) => interest.min(cap),
+            None => interest,
+        }
+    }
+}
+
+impl Default for EconomyRules {
+    fn default() -> Self {
+        Self {
+            sell_refund: default_sell_refund(),
+            wave_clear_bonus: 0,
+            no_lives_lost_bonus: 0,
+            interest_rate: 0.0,
+            interest_cap: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod economy_tests {
+    use crate::map::EconomyRules;
+
+    #[test]
+    fn interest() {
+        let rules = EconomyRules::default().with_interest(0.1, Some(30));
+        assert_eq!(rules.interest(0), 0);
+        assert_eq!(rules.interest(199), 19);
+        assert_eq!(rules.interest(1000), 30);
+        assert_eq!(EconomyRules::default().interest(1000), 0);
+    }
+}
@@ -0,0 +1,229 @@
+use crate::map::path::{PathDefinition, PathDefinitions};
+use crate::map::tiled::TiledMap;
+use crate::map::{EconomyRules, Map, MapInfo, PlacementRules, Size, WaveSchedule};
+use crate::math::{Rect, Vector2};
+use crate::resources::{read_json, ResourceError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path as FilePath;
+
+/// A rectangle given by three of its corners, `a` and `c` being opposite of each other.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RectDefinition {
+    a: Vector2,
+    b: Vector2,
+    c: Vector2,
+}
+
+impl RectDefinition {
+    pub fn new(a: Vector2, b: Vector2, c: Vector2) -> Self {
+        Self { a, b, c }
+    }
+
+    pub fn build(&self) -> Rect {
+        Rect::new(self.a.clone(), self.b.clone(), self.c.clone())
+    }
+}
+
+/// Imports objects from a map made with the Tiled editor. The file is relative to the map file.
+#[derive(Deserialize)]
+struct TiledImport {
+    file: String,
+    layer: String,
+    #[serde(default = "default_base_object")]
+    base_object: String,
+}
+
+fn default_base_object() -> String {
+    String::from("base")
+}
+
+/// The contents of a map file. Paths and bases can either be written in the file itself or
+/// imported from a Tiled map. Paths in the file replace imported paths of the same name.
+#[derive(Deserialize)]
+struct MapFile {
+    #[serde(flatten)]
+    info: MapInfo,
+    size: Size,
+    max_lives: u64,
+    #[serde(default)]
+    paths: PathDefinitions,
+    /// The path of enemies whose wave entry does not name one. Can be left out if the map only
+    /// has a single path.
+    #[serde(default)]
+    default_path: Option<String>,
+    /// The path flying enemies take. Without one they fly straight along their path.
+    #[serde(default)]
+    air_path: Option<String>,
+    /// Air paths of single paths by the name of the path, replacing `air_path` for them.
+    #[serde(default)]
+    air_paths: BTreeMap<String, String>,
+    #[serde(default)]
+    bases: Vec<RectDefinition>,
+    #[serde(default)]
+    tiled: Option<TiledImport>,
+    waves: WaveSchedule,
+    #[serde(default)]
+    placement: PlacementRules,
+    #[serde(default)]
+    economy: EconomyRules,
+}
+
+/// Reads a map file. The id of the map is the name of the file without its extension.
+pub fn load_map(path: &FilePath) -> Result<Map, ResourceError> {
+    build_map(read_json(path)?, path)
+}
+
+/// Builds the map from the contents of the file at `path`.
+fn build_map(mut file: MapFile, path: &FilePath) -> Result<Map, ResourceError> {
+    let error = |message: String| ResourceError::new(path, message);
+    let id = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut path_definitions = PathDefinitions::new();
+    let mut bases = vec![];
+    if let Some(import) = &file.tiled {
+        let dir = path.parent().unwrap_or_else(|| FilePath::new(""));
+        let tiled: TiledMap = read_json(&dir.join(&import.file))?;
+        path_definitions = tiled.import_paths(&import.layer).map_err(error)?;
+        bases = tiled
+            .import_rects(&import.layer, &import.base_object)
+            .map_err(error)?;
+    }
+    path_definitions.append(&mut file.paths);
+    if !file.bases.is_empty() {
+        bases = file.bases;
+    }
+
+    let paths = PathDefinition::build_all(&path_definitions).map_err(error)?;
+    let default_path = match file.default_path {
+        Some(default_path) => default_path,
+        None if paths.len() == 1 => paths.keys().next().unwrap().clone(),
+        None => {
+            return Err(error(String::from(
+                "Maps with several paths need a default_path",
+            )))
+        }
+    };
+    let names = file.waves.get_path_names().chain([default_path.as_str()]);
+    let air_paths = file
+        .air_paths
+        .iter()
+        .flat_map(|(path, air_path)| [path.as_str(), air_path.as_str()]);
+    for name in names.chain(file.air_path.as_deref()).chain(air_paths) {
+        if !paths.contains_key(name) {
+            return Err(error(format!("Unknown path {}", name)));
+        }
+    }
+    file.waves.validate().map_err(error)?;
+    if bases.is_empty() {
+        return Err(error(String::from("The map has no base")));
+    }
+    // Enemies only leave the map through a base, so every path has to end in one, air paths
+    // included.
+    let bases: Vec<Rect> = bases.iter().map(|base| base.build()).collect();
+    for (name, path) in &paths {
+        let end = path.coords_at(path.length());
+        if !bases.iter().any(|base| base.is_inside(&end)) {
+            return Err(error(format!("Path {} does not end in a base", name)));
+        }
+    }
+    if !(0.0..=1.0).contains(&file.economy.get_sell_refund()) {
+        return Err(error(String::from(
+            "The sell refund has to be between 0 and 1",
+        )));
+    }
+    if file.economy.get_interest_rate() < 0.0 {
+        return Err(error(String::from("The interest rate cannot be negative")));
+    }
+
+    Ok(Map::new(
+        file.info.with_id(id),
+        file.size,
+        file.max_lives,
+        paths,
+        default_path,
+        bases,
+        file.waves,
+    )
+    .with_placement(file.placement)
+    .with_economy(file.economy)
+    .with_air_path(file.air_path)
+    .with_air_paths(file.air_paths))
+}
+
+#[cfg(test)]
+mod file_tests {
+    use crate::entity::Movement;
+    use crate::map::file::{build_map, MapFile};
+    use crate::map::load_map;
+    use crate::resources::{read_json, RESOURCES_DIR};
+    use serde::Deserialize;
+    use serde_json::{json, Value};
+    use std::path::Path;
+
+    #[test]
+    fn load_resources() {
+        let map = load_map(&Path::new(RESOURCES_DIR).join("maps/map_1.json"))
+            .unwrap_or_else(|e| panic!("{}", e));
+
+        assert_eq!(map.get_id(), "map_1");
+        assert_eq!(map.get_max_lives(), 6);
+        assert_eq!(map.get_waves().get_wave_count(), 4);
+        assert_eq!(map.get_default_path().length(), 4970.0);
+
+        // Without an air path, flying enemies cut straight to the end of their path.
+        let path = map.get_default_path();
+        let route = map.get_route("main", Movement::Flying).unwrap();
+        assert!(route.length() < path.length());
+        let end = &route.coords_at(route.length()) - &path.coords_at(path.length());
+        assert!(end.magnitude() < 1e-9);
+    }
+
+    #[test]
+    fn paths_end_in_a_base() {
+        let path = Path::new(RESOURCES_DIR).join("maps/map_1.json");
+        let map: Value = read_json(&path).unwrap();
+        let build = |map: &Value| build_map(MapFile::deserialize(map).unwrap(), &path);
+        assert!(build(&map).is_ok());
+
+        let mut moved = map.clone();
+        moved["bases"] = json!([{
+            "a": { "x": 1800.0, "y": 0.0 },
+            "b": { "x": 1800.0, "y": 40.0 },
+            "c": { "x": 1840.0, "y": 40.0 },
+        }]);
+        assert!(build(&moved).is_err());
+
+        // A second base at the end of the path is enough.
+        moved["bases"]
+            .as_array_mut()
+            .unwrap()
+            .push(map["bases"][0].clone());
+        assert!(build(&moved).is_ok());
+    }
+
+    #[test]
+    fn air_paths_end_in_a_base() {
+        let path = Path::new(RESOURCES_DIR).join("maps/map_1.json");
+        let mut map: Value = read_json(&path).unwrap();
+        let build = |map: &Value| build_map(MapFile::deserialize(map).unwrap(), &path);
+        let air_path = |to: Value| {
+            json!({
+                "start": { "x": 0.0, "y": 180.0 },
+                "segments": [{ "type": "Line", "to": to }],
+            })
+        };
+
+        map["paths"]["air"] = air_path(json!({ "x": 160.0, "y": 1080.0 }));
+        map["default_path"] = json!("main");
+        map["air_path"] = json!("air");
+        assert!(build(&map).is_ok());
+
+        map["paths"]["short"] = air_path(json!({ "x": 160.0, "y": 900.0 }));
+        map["air_paths"] = json!({ "main": "short" });
+        assert!(build(&map).is_err());
+    }
+}
@@ -1,3 +1,34 @@
-mod level_1;
+use crate::map::{load_map, Map};
+use crate::resources::{list_json_files, ResourceError, RESOURCES_DIR};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
 
-pub use level_1::MAP_LEVEL_1;
\ No newline at end of file
+/// All playable maps by id.
+pub type LevelRegistry = BTreeMap<String, Arc<Map>>;
+
+/// Reads all map files in `<resources>/maps`.
+pub fn load_levels(resources: &Path) -> Result<LevelRegistry, ResourceError> {
+    let dir = resources.join("maps");
+    let mut levels = LevelRegistry::new();
+    for path in list_json_files(&dir)? {
+        let map = load_map(&path)?;
+        levels.insert(String::from(map.get_id()), Arc::new(map));
+    }
+
+    if levels.is_empty() {
+        return Err(ResourceError::new(&dir, String::from("No maps found")));
+    }
+
+    Ok(levels)
+}
+
+/// The level selected in a new lobby.
+pub fn get_default_level() -> &'static str {
+    LEVEL_REGISTRY.keys().next().unwrap()
+}
+
+lazy_static! {
+    pub static ref LEVEL_REGISTRY: LevelRegistry = load_levels(Path::new(RESOURCES_DIR))
+        .unwrap_or_else(|e| panic!("Could not load maps: {}", e));
+}
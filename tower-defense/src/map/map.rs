@@ -1,65 +1,300 @@
-use crate::map::path::Path;
-use crate::math::Rect;
-use serde::Serialize;
+use crate::entity::Movement;
+use crate::map::path::{Line, Path, PathComponent};
+use crate::map::{EconomyRules, PlacementError, PlacementRules, WaveSchedule};
+use crate::math::{Rect, Vector2};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Size {
     x: i32,
     y: i32,
 }
 
-#[derive(Serialize)]
-pub struct Map {
+/// Describes a map to the players, e.g. in the level selection.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MapInfo {
+    /// Name of the map file without its extension.
+    #[serde(skip_deserializing)]
+    id: String,
+    name: String,
+    #[serde(default)]
+    description: String,
     background_image: String,
     background_filler_image: String,
+}
+
+impl MapInfo {
+    pub fn new(
+        id: String,
+        name: String,
+        description: String,
+        background_image: String,
+        background_filler_image: String,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            description,
+            background_image,
+            background_filler_image,
+        }
+    }
+
+    pub(super) fn with_id(self, id: String) -> Self {
+        Self { id, ..self }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Map {
+    #[serde(flatten)]
+    info: MapInfo,
     size: Size,
     max_lives: u64,
 
     #[serde(skip_serializing)]
-    path: Path,
+    paths: BTreeMap<String, Path>,
+
+    #[serde(skip_serializing)]
+    default_path: String,
 
+    /// The path flying enemies take if their path has no air path of its own. Structures can be
+    /// built next to air paths.
     #[serde(skip_serializing)]
-    base: Rect,
+    air_path: Option<String>,
+
+    /// The air paths of single paths, by the name of the path.
+    #[serde(skip_serializing)]
+    air_paths: BTreeMap<String, String>,
+
+    /// Straight lines from the start to the end of each path, taken by flying enemies if there
+    /// is no air path. Paths that end where they start have none.
+    #[serde(skip_serializing)]
+    direct_paths: BTreeMap<String, Path>,
+
+    #[serde(skip_serializing)]
+    bases: Vec<Rect>,
+
+    #[serde(skip_serializing)]
+    waves: WaveSchedule,
+
+    placement: PlacementRules,
+    economy: EconomyRules,
 }
 
 impl Size {
     pub fn new(x: i32, y: i32) -> Size {
         Size { x, y }
     }
+
+    pub fn x(&self) -> i32 {
+        self.x
+    }
+
+    pub fn y(&self) -> i32 {
+        self.y
+    }
 }
 
 impl Map {
     pub fn new(
-        background_image: String,
-        background_filler_image: String,
+        info: MapInfo,
         size: Size,
         max_lives: u64,
-        path: Path,
-        base: Rect,
+        paths: BTreeMap<String, Path>,
+        default_path: String,
+        bases: Vec<Rect>,
+        waves: WaveSchedule,
     ) -> Map {
+        let direct_paths = paths
+            .iter()
+            .filter_map(|(name, path)| {
+                let line = Line::new(path.coords_at(0.0), path.coords_at(path.length()));
+                (line.length() > 0.0).then(|| (name.clone(), Path::new(vec![Box::new(line)])))
+            })
+            .collect();
         Map {
-            background_image,
-            background_filler_image,
+            info,
             size,
             max_lives,
-            path,
-            base,
+            paths,
+            default_path,
+            air_path: None,
+            air_paths: BTreeMap::new(),
+            direct_paths,
+            bases,
+            waves,
+            placement: PlacementRules::default(),
+            economy: EconomyRules::default(),
         }
     }
 
+    pub fn with_placement(self, placement: PlacementRules) -> Self {
+        Self { placement, ..self }
+    }
+
+    pub fn with_economy(self, economy: EconomyRules) -> Self {
+        Self { economy, ..self }
+    }
+
+    pub fn with_air_path(self, air_path: Option<String>) -> Self {
+        Self { air_path, ..self }
+    }
+
+    pub fn with_air_paths(self, air_paths: BTreeMap<String, String>) -> Self {
+        Self { air_paths, ..self }
+    }
+
+    pub fn get_id(&self) -> &str {
+        &self.info.id
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.info.name
+    }
+
     pub fn get_size(&self) -> Size {
         self.size.clone()
     }
 
-    pub fn get_path(&self) -> &Path {
-        &self.path
+    pub fn get_path(&self, name: &str) -> Option<&Path> {
+        self.paths.get(name)
+    }
+
+    /// The way an enemy sent along the path `name` actually takes, depending on its movement.
+    /// Flying enemies follow the ground path if it has neither an air path nor a direct route.
+    pub fn get_route(&self, name: &str, movement: Movement) -> Option<&Path> {
+        let air_path = self.air_paths.get(name).or(self.air_path.as_ref());
+        match (movement, air_path) {
+            (Movement::Ground, _) => self.paths.get(name),
+            (Movement::Flying, Some(air_path)) => self.paths.get(air_path),
+            (Movement::Flying, None) => {
+                self.direct_paths.get(name).or_else(|| self.paths.get(name))
+            }
+        }
+    }
+
+    fn is_air_path(&self, name: &str) -> bool {
+        self.air_path.as_deref() == Some(name) || self.air_paths.values().any(|x| x == name)
+    }
+
+    pub fn get_default_path_name(&self) -> &str {
+        &self.default_path
+    }
+
+    pub fn get_default_path(&self) -> &Path {
+        &self.paths[&self.default_path]
     }
 
     pub fn get_max_lives(&self) -> u64 {
         self.max_lives
     }
 
-    pub fn get_base(&self) -> &Rect {
-        &self.base
+    pub fn get_bases(&self) -> &[Rect] {
+        &self.bases
+    }
+
+    pub fn get_waves(&self) -> &WaveSchedule {
+        &self.waves
+    }
+
+    pub fn get_economy(&self) -> &EconomyRules {
+        &self.economy
+    }
+
+    pub fn get_placement(&self) -> &PlacementRules {
+        &self.placement
+    }
+
+    /// Checks whether a structure with the given footprint can be built on the map. Other
+    /// structures are not taken into account.
+    pub fn check_placement(&self, center: &Vector2, radius: f64) -> Result<(), PlacementError> {
+        if center.x() - radius < 0.0
+            || center.y() - radius < 0.0
+            || center.x() + radius > self.size.x as f64
+            || center.y() + radius > self.size.y as f64
+        {
+            return Err(PlacementError::OutOfBounds);
+        }
+
+        let clearance = radius + self.placement.get_path_clearance();
+        if self
+            .paths
+            .iter()
+            .filter(|(name, _)| !self.is_air_path(name))
+            .any(|(_, path)| path.distance_to(center) < clearance)
+        {
+            return Err(PlacementError::OnPath);
+        }
+
+        self.placement.check_zones(center, radius)
+    }
+}
+
+#[cfg(test)]
+mod map_tests {
+    use crate::entity::Movement;
+    use crate::map::path::{Line, Path, PathComponent};
+    use crate::map::{Map, MapInfo, Size, WaveSchedule};
+    use crate::math::Vector2;
+    use std::collections::BTreeMap;
+
+    fn path(points: &[(f64, f64)]) -> Path {
+        Path::new(
+            points
+                .windows(2)
+                .map(|line| {
+                    Box::new(Line::new(
+                        Vector2::new(line[0].0, line[0].1),
+                        Vector2::new(line[1].0, line[1].1),
+                    )) as Box<dyn PathComponent + Send + Sync>
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn routes() {
+        let paths = BTreeMap::from([
+            (
+                String::from("loop"),
+                path(&[(0.0, 0.0), (100.0, 0.0), (100.0, 100.0), (0.0, 0.0)]),
+            ),
+            (String::from("north"), path(&[(0.0, 500.0), (300.0, 900.0)])),
+            (String::from("south"), path(&[(0.0, 800.0), (300.0, 400.0)])),
+            (String::from("air"), path(&[(0.0, 500.0), (900.0, 500.0)])),
+        ]);
+        let info = MapInfo::new(
+            String::from("test"),
+            String::from("Test"),
+            String::new(),
+            String::new(),
+            String::new(),
+        );
+        let waves = WaveSchedule::new(0.0, 0.0, vec![], None);
+        let map = Map::new(
+            info,
+            Size::new(1000, 1000),
+            10,
+            paths,
+            String::from("loop"),
+            vec![],
+            waves,
+        )
+        .with_air_paths(BTreeMap::from([(
+            String::from("north"),
+            String::from("air"),
+        )]));
+        let route = |name: &str| map.get_route(name, Movement::Flying).unwrap().length();
+
+        // A path that ends where it starts has no direct route.
+        assert_eq!(route("loop"), map.get_path("loop").unwrap().length());
+        assert_eq!(route("north"), 900.0);
+        assert_eq!(route("south"), 500.0);
+        // Towers can be built on air paths.
+        assert!(map
+            .check_placement(&Vector2::new(600.0, 500.0), 20.0)
+            .is_ok());
     }
 }
@@ -1,7 +1,18 @@
+mod economy;
+mod file;
 pub mod levels;
+#[allow(clippy::module_inception)]
 mod map;
 pub mod path;
+mod placement;
+mod tiled;
 mod wave;
 
-pub use map::{Map, Size};
-pub use wave::Wave;
+pub use economy::EconomyRules;
+pub use file::{load_map, RectDefinition};
+pub use map::{Map, MapInfo, Size};
+pub use placement::{PlacementError, PlacementRules};
+pub use tiled::TiledMap;
+pub use wave::{
+    BossEntry, EndlessWaves, EnemyGroup, WaveDefinition, WaveProgress, WaveSchedule, WaveState,
+};
@@ -0,0 +1,298 @@
+use crate::map::path::PathComponent;
+use crate::math::Vector2;
+use std::f64::consts::PI;
+
+/// Number of straight pieces a bezier curve is split into to measure its length.
+const LENGTH_SAMPLES: usize = 256;
+
+/****************************************
+* Arc Length Table
+*****************************************/
+
+/// Maps the distance travelled along a curve back to the curve parameter, so that curves whose
+/// parameter does not move at constant speed can still be walked along at constant speed.
+struct ArcLengthTable {
+    /// Length of the curve from its start to sample `i`, sample `i` being at parameter
+    /// `i / LENGTH_SAMPLES`.
+    lengths: Vec<f64>,
+}
+
+impl ArcLengthTable {
+    fn new(curve: impl Fn(f64) -> Vector2) -> Self {
+        let mut lengths = Vec::with_capacity(LENGTH_SAMPLES + 1);
+        let mut length = 0.0;
+        let mut previous = curve(0.0);
+        lengths.push(length);
+        for i in 1..=LENGTH_SAMPLES {
+            let current = curve(i as f64 / LENGTH_SAMPLES as f64);
+            length += (&current - &previous).magnitude();
+            lengths.push(length);
+            previous = current;
+        }
+
+        Self { lengths }
+    }
+
+    fn length(&self) -> f64 {
+        self.lengths[LENGTH_SAMPLES]
+    }
+
+    /// The curve parameter after travelling `t` (0 to 1) of the length of the curve.
+    fn parameter_at(&self, t: f64) -> f64 {
+        let target = t * self.length();
+        let i = self.lengths.partition_point(|length| *length < target);
+        if i == 0 {
+            return 0.0;
+        }
+        if i > LENGTH_SAMPLES {
+            return 1.0;
+        }
+
+        let before = self.lengths[i - 1];
+        let piece = self.lengths[i] - before;
+        let fraction = if piece > 0.0 {
+            (target - before) / piece
+        } else {
+            0.0
+        };
+        (i as f64 - 1.0 + fraction) / LENGTH_SAMPLES as f64
+    }
+}
+
+/****************************************
+* Quadratic Bezier
+*****************************************/
+
+pub struct QuadraticBezier {
+    start: Vector2,
+    control: Vector2,
+    end: Vector2,
+    table: ArcLengthTable,
+}
+
+impl QuadraticBezier {
+    pub fn new(start: Vector2, control: Vector2, end: Vector2) -> Self {
+        let table = ArcLengthTable::new(|u| Self::evaluate(&start, &control, &end, u));
+        Self {
+            start,
+            control,
+            end,
+            table,
+        }
+    }
+
+    fn evaluate(start: &Vector2, control: &Vector2, end: &Vector2, u: f64) -> Vector2 {
+        let v = 1.0 - u;
+        &(&(start * (v * v)) + &(control * (2.0 * v * u))) + &(end * (u * u))
+    }
+}
+
+impl PathComponent for QuadraticBezier {
+    fn length(&self) -> f64 {
+        self.table.length()
+    }
+
+    fn coords_at(&self, t: f64) -> Vector2 {
+        let u = self.table.parameter_at(t);
+        Self::evaluate(&self.start, &self.control, &self.end, u)
+    }
+
+    fn start(&self) -> Vector2 {
+        self.start.clone()
+    }
+
+    fn end(&self) -> Vector2 {
+        self.end.clone()
+    }
+}
+
+/****************************************
+* Cubic Bezier
+*****************************************/
+
+pub struct CubicBezier {
+    start: Vector2,
+    control1: Vector2,
+    control2: Vector2,
+    end: Vector2,
+    table: ArcLengthTable,
+}
+
+impl CubicBezier {
+    pub fn new(start: Vector2, control1: Vector2, control2: Vector2, end: Vector2) -> Self {
+        let table = ArcLengthTable::new(|u| Self::evaluate(&start, &control1, &control2, &end, u));
+        Self {
+            start,
+            control1,
+            control2,
+            end,
+            table,
+        }
+    }
+
+    fn evaluate(
+        start: &Vector2,
+        control1: &Vector2,
+        control2: &Vector2,
+        end: &Vector2,
+        u: f64,
+    ) -> Vector2 {
+        let v = 1.0 - u;
+        let a = &(start * (v * v * v)) + &(control1 * (3.0 * v * v * u));
+        let b = &(control2 * (3.0 * v * u * u)) + &(end * (u * u * u));
+        &a + &b
+    }
+}
+
+impl PathComponent for CubicBezier {
+    fn length(&self) -> f64 {
+        self.table.length()
+    }
+
+    fn coords_at(&self, t: f64) -> Vector2 {
+        let u = self.table.parameter_at(t);
+        Self::evaluate(&self.start, &self.control1, &self.control2, &self.end, u)
+    }
+
+    fn start(&self) -> Vector2 {
+        self.start.clone()
+    }
+
+    fn end(&self) -> Vector2 {
+        self.end.clone()
+    }
+}
+
+/****************************************
+* Circular Arc
+*****************************************/
+
+/// A part of a circle. Angles are in radians and measured from the x axis towards the y axis,
+/// which is clockwise on screen as the y axis points down.
+pub struct CircularArc {
+    center: Vector2,
+    radius: f64,
+    start_angle: f64,
+    sweep: f64,
+}
+
+impl CircularArc {
+    pub fn new(center: Vector2, radius: f64, start_angle: f64, sweep: f64) -> Self {
+        Self {
+            center,
+            radius,
+            start_angle,
+            sweep,
+        }
+    }
+
+    /// Creates the arc around `center` that starts at `start` and turns by `sweep` degrees.
+    pub fn from_start(start: &Vector2, center: Vector2, sweep: f64) -> Self {
+        let offset = start - &center;
+        let radius = offset.magnitude();
+        let start_angle = offset.y().atan2(offset.x());
+        Self::new(center, radius, start_angle, sweep * PI / 180.0)
+    }
+}
+
+impl PathComponent for CircularArc {
+    fn length(&self) -> f64 {
+        self.radius * self.sweep.abs()
+    }
+
+    fn coords_at(&self, t: f64) -> Vector2 {
+        let angle = self.start_angle + self.sweep * t;
+        Vector2::new(
+            self.center.x() + self.radius * angle.cos(),
+            self.center.y() + self.radius * angle.sin(),
+        )
+    }
+
+    fn start(&self) -> Vector2 {
+        self.coords_at(0.0)
+    }
+
+    fn end(&self) -> Vector2 {
+        self.coords_at(1.0)
+    }
+}
+
+#[cfg(test)]
+mod curve_tests {
+    use crate::map::path::{CircularArc, CubicBezier, PathComponent, QuadraticBezier};
+    use crate::math::Vector2;
+    use std::f64::consts::PI;
+
+    fn assert_close(a: &Vector2, b: &Vector2) {
+        assert!((a - b).magnitude() < 1e-6, "{:?} != {:?}", a, b);
+    }
+
+    /// Checks that equal steps in `t` cover equal distances on the curve.
+    fn assert_constant_speed(component: &dyn PathComponent) {
+        let steps = 100;
+        let expected = component.length() / steps as f64;
+        for i in 0..steps {
+            let a = component.coords_at(i as f64 / steps as f64);
+            let b = component.coords_at((i + 1) as f64 / steps as f64);
+            let distance = (&b - &a).magnitude();
+            assert!(
+                (distance - expected).abs() < expected * 0.01,
+                "step {} covers {} instead of {}",
+                i,
+                distance,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn quadratic_bezier() {
+        let curve = QuadraticBezier::new(
+            Vector2::new(0.0, 0.0),
+            Vector2::new(100.0, 0.0),
+            Vector2::new(100.0, 100.0),
+        );
+
+        assert_close(&curve.coords_at(0.0), &Vector2::new(0.0, 0.0));
+        assert_close(&curve.coords_at(1.0), &Vector2::new(100.0, 100.0));
+        assert!(curve.length() > 141.0 && curve.length() < 200.0);
+        assert_constant_speed(&curve);
+    }
+
+    #[test]
+    fn cubic_bezier() {
+        let curve = CubicBezier::new(
+            Vector2::new(0.0, 0.0),
+            Vector2::new(0.0, 200.0),
+            Vector2::new(200.0, -100.0),
+            Vector2::new(200.0, 100.0),
+        );
+
+        assert_close(&curve.coords_at(0.0), &Vector2::new(0.0, 0.0));
+        assert_close(&curve.coords_at(1.0), &Vector2::new(200.0, 100.0));
+        assert_constant_speed(&curve);
+
+        // A cubic bezier with evenly spaced control points on a line is a straight line.
+        let line = CubicBezier::new(
+            Vector2::new(0.0, 0.0),
+            Vector2::new(10.0, 0.0),
+            Vector2::new(20.0, 0.0),
+            Vector2::new(30.0, 0.0),
+        );
+        assert!((line.length() - 30.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn circular_arc() {
+        let arc = CircularArc::from_start(&Vector2::new(100.0, 0.0), Vector2::new(0.0, 0.0), 90.0);
+
+        assert!((arc.length() - 50.0 * PI).abs() < 1e-9);
+        assert_close(&arc.start(), &Vector2::new(100.0, 0.0));
+        assert_close(&arc.end(), &Vector2::new(0.0, 100.0));
+        assert_constant_speed(&arc);
+
+        let counter_clockwise =
+            CircularArc::from_start(&Vector2::new(100.0, 0.0), Vector2::new(0.0, 0.0), -90.0);
+        assert_close(&counter_clockwise.end(), &Vector2::new(0.0, -100.0));
+    }
+}
@@ -0,0 +1,285 @@
+use crate::map::path::{
+    CircularArc, CubicBezier, Line, Path, PathComponent, PathSection, QuadraticBezier,
+};
+use crate::math::Vector2;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A single piece of a path as written in a map file. Every segment starts where the previous
+/// one ended.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type")]
+pub enum SegmentDefinition {
+    Line {
+        to: Vector2,
+    },
+    QuadraticBezier {
+        control: Vector2,
+        to: Vector2,
+    },
+    CubicBezier {
+        control1: Vector2,
+        control2: Vector2,
+        to: Vector2,
+    },
+    /// Turns around `center` by `angle` degrees, positive angles turn clockwise on screen.
+    Arc {
+        center: Vector2,
+        angle: f64,
+    },
+}
+
+impl SegmentDefinition {
+    fn build(&self, start: Vector2) -> Box<dyn PathComponent + Send + Sync> {
+        match self {
+            Self::Line { to } => Box::new(Line::new(start, to.clone())),
+            Self::QuadraticBezier { control, to } => {
+                Box::new(QuadraticBezier::new(start, control.clone(), to.clone()))
+            }
+            Self::CubicBezier {
+                control1,
+                control2,
+                to,
+            } => Box::new(CubicBezier::new(
+                start,
+                control1.clone(),
+                control2.clone(),
+                to.clone(),
+            )),
+            Self::Arc { center, angle } => {
+                Box::new(CircularArc::from_start(&start, center.clone(), *angle))
+            }
+        }
+    }
+}
+
+/// A position on another path, given as the distance from its start.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PathReference {
+    path: String,
+    distance: f64,
+}
+
+/// The description of a path in a map file.
+///
+/// A path either starts at `start`, or forks off another path by following it up to the given
+/// distance first. Likewise it can merge into another path after its own segments and follow that
+/// one from the given distance to its end.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PathDefinition {
+    #[serde(default)]
+    start: Option<Vector2>,
+    #[serde(default)]
+    fork: Option<PathReference>,
+    #[serde(default)]
+    segments: Vec<SegmentDefinition>,
+    #[serde(default)]
+    merge: Option<PathReference>,
+}
+
+/// All paths of a map by name.
+pub type PathDefinitions = BTreeMap<String, PathDefinition>;
+
+/// How far the end of a path may be away from the point it merges into.
+const MERGE_TOLERANCE: f64 = 1.0;
+
+impl PathDefinition {
+    pub fn new(start: Vector2, segments: Vec<SegmentDefinition>) -> Self {
+        Self {
+            start: Some(start),
+            fork: None,
+            segments,
+            merge: None,
+        }
+    }
+
+    /// Creates a path of straight lines through all `points`.
+    pub fn from_points(points: Vec<Vector2>) -> Result<Self, String> {
+        let mut points = points.into_iter();
+        let start = points
+            .next()
+            .ok_or_else(|| String::from("A path needs at least two points"))?;
+        let segments: Vec<SegmentDefinition> =
+            points.map(|to| SegmentDefinition::Line { to }).collect();
+
+        Ok(Self::new(start, segments))
+    }
+
+    /// Builds a path that does not fork off or merge into other paths.
+    pub fn build(&self) -> Result<Path, String> {
+        self.build_with(&PathDefinitions::new(), &mut vec![])
+    }
+
+    /// Builds every path of `definitions`, resolving forks and merges between them.
+    pub fn build_all(definitions: &PathDefinitions) -> Result<BTreeMap<String, Path>, String> {
+        let mut paths = BTreeMap::new();
+        for (name, definition) in definitions {
+            let path = definition
+                .build_with(definitions, &mut vec![name.clone()])
+                .map_err(|e| format!("Path {}: {}", name, e))?;
+            paths.insert(name.clone(), path);
+        }
+
+        Ok(paths)
+    }
+
+    /// `building` contains the names of the paths currently being built to detect cycles.
+    fn build_with(
+        &self,
+        definitions: &PathDefinitions,
+        building: &mut Vec<String>,
+    ) -> Result<Path, String> {
+        let mut components: Vec<Box<dyn PathComponent + Send + Sync>> = vec![];
+        let mut start = match (&self.start, &self.fork) {
+            (_, Some(fork)) => {
+                let section = fork.resolve(definitions, building, 0.0, fork.distance)?;
+                let end = section.end();
+                if section.length() > 0.0 {
+                    components.push(Box::new(section));
+                }
+                end
+            }
+            (Some(start), None) => start.clone(),
+            (None, None) => return Err(String::from("A path needs a start or a fork")),
+        };
+
+        for (i, segment) in self.segments.iter().enumerate() {
+            let component = segment.build(start);
+            if component.length() <= 0.0 {
+                return Err(format!("Segment {} has a length of 0", i + 1));
+            }
+            start = component.end();
+            components.push(component);
+        }
+
+        if let Some(merge) = &self.merge {
+            let section = merge.resolve(definitions, building, merge.distance, f64::INFINITY)?;
+            if (&section.start() - &start).magnitude() > MERGE_TOLERANCE {
+                return Err(format!(
+                    "The path ends at {:?} but merges into {} at {:?}",
+                    start,
+                    merge.path,
+                    section.start()
+                ));
+            }
+            if section.length() > 0.0 {
+                components.push(Box::new(section));
+            }
+        }
+
+        if components.is_empty() {
+            return Err(String::from("A path needs at least one segment"));
+        }
+
+        let path = Path::new(components);
+        if path.length() <= 0.0 {
+            return Err(String::from("A path must not have a length of 0"));
+        }
+
+        Ok(path)
+    }
+}
+
+impl PathReference {
+    /// Builds the part of the referenced path between `from` and `to`.
+    fn resolve(
+        &self,
+        definitions: &PathDefinitions,
+        building: &mut Vec<String>,
+        from: f64,
+        to: f64,
+    ) -> Result<PathSection, String> {
+        if building.contains(&self.path) {
+            return Err(format!("Path {} references itself", self.path));
+        }
+        let definition = definitions
+            .get(&self.path)
+            .ok_or_else(|| format!("Unknown path {}", self.path))?;
+
+        building.push(self.path.clone());
+        let path = definition.build_with(definitions, building)?;
+        building.pop();
+
+        if self.distance < 0.0 || self.distance > path.length() {
+            return Err(format!(
+                "Distance {} is not on path {} with a length of {}",
+                self.distance,
+                self.path,
+                path.length()
+            ));
+        }
+        let to = to.min(path.length());
+        Ok(PathSection::new(path, from, to))
+    }
+}
+
+#[cfg(test)]
+mod definition_tests {
+    use crate::map::path::{PathDefinition, PathDefinitions};
+    use crate::math::Vector2;
+
+    #[test]
+    fn build_curved_path() {
+        let definition: PathDefinition = serde_json::from_str(
+            r#"{
+                "start": { "x": 0.0, "y": 0.0 },
+                "segments": [
+                    { "type": "Line", "to": { "x": 100.0, "y": 0.0 } },
+                    { "type": "Arc", "center": { "x": 100.0, "y": 100.0 }, "angle": 90.0 },
+                    { "type": "QuadraticBezier", "control": { "x": 200.0, "y": 200.0 }, "to": { "x": 100.0, "y": 200.0 } },
+                    { "type": "CubicBezier", "control1": { "x": 0.0, "y": 200.0 }, "control2": { "x": 0.0, "y": 300.0 }, "to": { "x": 100.0, "y": 300.0 } }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let path = definition.build().unwrap();
+
+        let end = path.coords_at(path.length());
+        assert!((&end - &Vector2::new(100.0, 300.0)).magnitude() < 1e-6);
+        // The arc ends at the right of its center.
+        let arc_end = path.coords_at(100.0 + 50.0 * std::f64::consts::PI);
+        assert!((&arc_end - &Vector2::new(200.0, 100.0)).magnitude() < 1e-6);
+
+        let repeated_point = PathDefinition::from_points(vec![
+            Vector2::new(0.0, 0.0),
+            Vector2::new(100.0, 0.0),
+            Vector2::new(100.0, 0.0),
+        ])
+        .unwrap();
+        assert!(repeated_point.build().is_err());
+    }
+
+    #[test]
+    fn fork_and_merge() {
+        let definitions: PathDefinitions = serde_json::from_str(
+            r#"{
+                "main": {
+                    "start": { "x": 0.0, "y": 0.0 },
+                    "segments": [{ "type": "Line", "to": { "x": 300.0, "y": 0.0 } }]
+                },
+                "detour": {
+                    "fork": { "path": "main", "distance": 100.0 },
+                    "segments": [
+                        { "type": "Line", "to": { "x": 100.0, "y": 100.0 } },
+                        { "type": "Line", "to": { "x": 200.0, "y": 100.0 } },
+                        { "type": "Line", "to": { "x": 200.0, "y": 0.0 } }
+                    ],
+                    "merge": { "path": "main", "distance": 200.0 }
+                }
+            }"#,
+        )
+        .unwrap();
+        let paths = PathDefinition::build_all(&definitions).unwrap();
+
+        let detour = &paths["detour"];
+        assert_eq!(detour.length(), 500.0);
+        assert!((&detour.coords_at(50.0) - &Vector2::new(50.0, 0.0)).magnitude() < 1e-9);
+        assert!((&detour.coords_at(250.0) - &Vector2::new(150.0, 100.0)).magnitude() < 1e-9);
+        assert!((&detour.coords_at(500.0) - &Vector2::new(300.0, 0.0)).magnitude() < 1e-9);
+
+        let cycle: PathDefinitions =
+            serde_json::from_str(r#"{ "a": { "fork": { "path": "a", "distance": 0.0 } } }"#)
+                .unwrap();
+        assert!(PathDefinition::build_all(&cycle).is_err());
+    }
+}
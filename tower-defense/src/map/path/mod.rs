@@ -1,3 +1,8 @@
+mod curve;
+mod definition;
+#[allow(clippy::module_inception)]
 mod path;
 
-pub use path::*;
\ No newline at end of file
+pub use curve::{CircularArc, CubicBezier, QuadraticBezier};
+pub use definition::{PathDefinition, PathDefinitions, PathReference, SegmentDefinition};
+pub use path::*;
@@ -1,4 +1,7 @@
-use crate::math::Vector2;
+use crate::math::{distance_to_segment, project_onto_segment, Vector2};
+
+/// Distance between the points of the outline that is used to measure distances to a path.
+const OUTLINE_STEP: f64 = 10.0;
 
 pub trait PathComponent {
     fn length(&self) -> f64;
@@ -28,10 +31,10 @@ impl PathComponent for Line {
     }
 
     fn coords_at(&self, t: f64) -> Vector2 {
-        return Vector2::new(
+        Vector2::new(
             self.start.x() + (self.end.x() - self.start.x()) * t,
             self.start.y() + (self.end.y() - self.start.y()) * t,
-        );
+        )
     }
 
     fn start(&self) -> Vector2 {
@@ -43,17 +46,93 @@ impl PathComponent for Line {
     }
 }
 
+/// The part of another path between two distances, used where paths fork or merge.
+pub struct PathSection {
+    path: Path,
+    from: f64,
+    to: f64,
+}
+
+impl PathSection {
+    pub fn new(path: Path, from: f64, to: f64) -> Self {
+        Self { path, from, to }
+    }
+}
+
+impl PathComponent for PathSection {
+    fn length(&self) -> f64 {
+        self.to - self.from
+    }
+
+    fn coords_at(&self, t: f64) -> Vector2 {
+        self.path.coords_at(self.from + (self.to - self.from) * t)
+    }
+
+    fn start(&self) -> Vector2 {
+        self.coords_at(0.0)
+    }
+
+    fn end(&self) -> Vector2 {
+        self.coords_at(1.0)
+    }
+}
+
 pub struct Path {
     path: Vec<Box<dyn PathComponent + Send + Sync>>,
     end: Vector2,
     length: f64,
+    /// Points along the path, used to find the distance of a point to the path.
+    outline: Vec<Vector2>,
+    /// How far along the path each point of the outline is.
+    outline_distances: Vec<f64>,
 }
 
 impl Path {
     pub fn new(path: Vec<Box<dyn PathComponent + Send + Sync>>) -> Path {
         let length = path.iter().map(|x| x.length()).sum();
         let end = path.last().unwrap().end();
-        Path { path, end, length }
+        let mut outline = vec![path[0].start()];
+        let mut outline_distances = vec![0.0];
+        let mut travelled = 0.0;
+        for component in &path {
+            let steps = (component.length() / OUTLINE_STEP).ceil().max(1.0) as usize;
+            for i in 1..=steps {
+                let t = i as f64 / steps as f64;
+                outline.push(component.coords_at(t));
+                outline_distances.push(travelled + component.length() * t);
+            }
+            travelled += component.length();
+        }
+        Path {
+            path,
+            end,
+            length,
+            outline,
+            outline_distances,
+        }
+    }
+
+    /// The shortest distance from `point` to any part of the path.
+    pub fn distance_to(&self, point: &Vector2) -> f64 {
+        self.outline
+            .windows(2)
+            .map(|segment| distance_to_segment(point, &segment[0], &segment[1]))
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// How far along the path the point of the path closest to `point` is.
+    pub fn distance_along(&self, point: &Vector2) -> f64 {
+        self.outline
+            .windows(2)
+            .zip(self.outline_distances.windows(2))
+            .map(|(segment, distances)| {
+                let t = project_onto_segment(point, &segment[0], &segment[1]);
+                let closest = &segment[0] + &(&(&segment[1] - &segment[0]) * t);
+                let along = distances[0] + (distances[1] - distances[0]) * t;
+                ((point - &closest).magnitude(), along)
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map_or(0.0, |(_, along)| along)
     }
 
     pub fn length(&self) -> f64 {
@@ -124,4 +203,19 @@ mod path_tests {
         println!("{:#?}", path.coords_at(0.9));
         println!("{:#?}", path.coords_at(1.0));
     }
+
+    #[test]
+    fn distance_along() {
+        let path = Path::new(vec![
+            Box::new(Line::new(Vector2::new(0.0, 0.0), Vector2::new(100.0, 0.0))),
+            Box::new(Line::new(
+                Vector2::new(100.0, 0.0),
+                Vector2::new(100.0, 100.0),
+            )),
+        ]);
+
+        assert_eq!(path.distance_along(&Vector2::new(150.0, 50.0)), 150.0);
+        assert_eq!(path.distance_along(&Vector2::new(30.0, -20.0)), 30.0);
+        assert_eq!(path.distance_along(&Vector2::new(-50.0, 0.0)), 0.0);
+    }
 }
@@ -0,0 +1,146 @@
+use crate::math::{Polygon, Vector2};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fmt::Formatter;
+
+/// Where structures may be built on a map.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PlacementRules {
+    /// Space that has to be left between a structure and the middle of a path, in addition to
+    /// the radius of the structure.
+    #[serde(default)]
+    path_clearance: f64,
+    /// If not empty, structures can only be built inside of these zones.
+    #[serde(default)]
+    buildable: Vec<Polygon>,
+    /// Zones in which nothing can be built.
+    #[serde(default)]
+    unbuildable: Vec<Polygon>,
+}
+
+impl PlacementRules {
+    pub fn new(path_clearance: f64, buildable: Vec<Polygon>, unbuildable: Vec<Polygon>) -> Self {
+        Self {
+            path_clearance,
+            buildable,
+            unbuildable,
+        }
+    }
+
+    pub fn get_path_clearance(&self) -> f64 {
+        self.path_clearance
+    }
+
+    /// Checks the build zones for a structure with the given footprint. It has to lie completely
+    /// inside a buildable zone and must not reach into an unbuildable one.
+    pub fn check_zones(&self, center: &Vector2, radius: f64) -> Result<(), PlacementError> {
+        if !self.buildable.is_empty()
+            && !self
+                .buildable
+                .iter()
+                .any(|zone| zone.contains_circle(center, radius))
+        {
+            return Err(PlacementError::OutsideBuildZone);
+        }
+        if self
+            .unbuildable
+            .iter()
+            .any(|zone| zone.overlaps_circle(center, radius))
+        {
+            return Err(PlacementError::InNoBuildZone);
+        }
+
+        Ok(())
+    }
+}
+
+/// Why a structure could not be placed.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlacementError {
+    /// The structure would reach over the edge of the map.
+    OutOfBounds,
+    /// The structure would stand on or too close to a path.
+    OnPath,
+    /// The map has build zones and the structure is not inside any of them.
+    OutsideBuildZone,
+    /// The structure would stand in a zone in which nothing can be built.
+    InNoBuildZone,
+    /// The structure would overlap another structure.
+    Obstructed,
+}
+
+impl fmt::Display for PlacementError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Self::OutOfBounds => "Out of the map",
+            Self::OnPath => "Too close to a path",
+            Self::OutsideBuildZone => "Outside of the build zones",
+            Self::InNoBuildZone => "In a no build zone",
+            Self::Obstructed => "Area obstructed",
+        };
+        write!(f, "{}", message)
+    }
+}
+
+impl std::error::Error for PlacementError {}
+
+#[cfg(test)]
+mod placement_tests {
+    use crate::map::{load_map, PlacementError, PlacementRules};
+    use crate::math::{Polygon, Vector2};
+    use crate::resources::RESOURCES_DIR;
+    use std::path::Path;
+
+    fn square(x: f64, y: f64, size: f64) -> Polygon {
+        Polygon::new(vec![
+            Vector2::new(x, y),
+            Vector2::new(x + size, y),
+            Vector2::new(x + size, y + size),
+            Vector2::new(x, y + size),
+        ])
+    }
+
+    #[test]
+    fn check_placement() {
+        let map = load_map(&Path::new(RESOURCES_DIR).join("maps/map_1.json")).unwrap();
+
+        assert_eq!(
+            map.check_placement(&Vector2::new(1000.0, 450.0), 50.0),
+            Ok(())
+        );
+        assert_eq!(
+            map.check_placement(&Vector2::new(30.0, 450.0), 50.0),
+            Err(PlacementError::OutOfBounds)
+        );
+        assert_eq!(
+            map.check_placement(&Vector2::new(1000.0, 200.0), 50.0),
+            Err(PlacementError::OnPath)
+        );
+    }
+
+    #[test]
+    fn check_zones() {
+        let rules = PlacementRules::new(0.0, vec![square(0.0, 0.0, 100.0)], vec![]);
+        assert_eq!(rules.check_zones(&Vector2::new(50.0, 50.0), 30.0), Ok(()));
+        assert_eq!(
+            rules.check_zones(&Vector2::new(150.0, 50.0), 30.0),
+            Err(PlacementError::OutsideBuildZone)
+        );
+        // The center is inside, but the structure reaches out of the zone.
+        assert_eq!(
+            rules.check_zones(&Vector2::new(80.0, 50.0), 30.0),
+            Err(PlacementError::OutsideBuildZone)
+        );
+
+        let rules = PlacementRules::new(0.0, vec![], vec![square(0.0, 0.0, 100.0)]);
+        assert_eq!(rules.check_zones(&Vector2::new(150.0, 50.0), 30.0), Ok(()));
+        assert_eq!(
+            rules.check_zones(&Vector2::new(50.0, 50.0), 30.0),
+            Err(PlacementError::InNoBuildZone)
+        );
+        assert_eq!(
+            rules.check_zones(&Vector2::new(120.0, 50.0), 30.0),
+            Err(PlacementError::InNoBuildZone)
+        );
+    }
+}
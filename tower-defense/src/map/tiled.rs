@@ -0,0 +1,144 @@
+use crate::map::path::{PathDefinition, PathDefinitions};
+use crate::map::RectDefinition;
+use crate::math::Vector2;
+use serde::Deserialize;
+
+/****************************************
+* Tiled Map
+*****************************************/
+
+/// The parts of a map exported from the Tiled editor as json that are used to import paths and
+/// areas. Only object layers are read, tile layers are ignored.
+#[derive(Deserialize)]
+pub struct TiledMap {
+    #[serde(default)]
+    layers: Vec<TiledLayer>,
+}
+
+#[derive(Deserialize)]
+struct TiledLayer {
+    name: String,
+    #[serde(default)]
+    objects: Vec<TiledObject>,
+    /// Child layers of group layers.
+    #[serde(default)]
+    layers: Vec<TiledLayer>,
+}
+
+#[derive(Deserialize)]
+struct TiledObject {
+    #[serde(default)]
+    name: String,
+    x: f64,
+    y: f64,
+    #[serde(default)]
+    width: f64,
+    #[serde(default)]
+    height: f64,
+    #[serde(default)]
+    rotation: f64,
+    #[serde(default)]
+    polyline: Option<Vec<TiledPoint>>,
+}
+
+#[derive(Deserialize)]
+struct TiledPoint {
+    x: f64,
+    y: f64,
+}
+
+impl TiledMap {
+    fn find_layer(&self, name: &str) -> Option<&TiledLayer> {
+        fn find<'a>(layers: &'a [TiledLayer], name: &str) -> Option<&'a TiledLayer> {
+            layers.iter().find_map(|layer| {
+                if layer.name == name {
+                    Some(layer)
+                } else {
+                    find(&layer.layers, name)
+                }
+            })
+        }
+
+        find(&self.layers, name)
+    }
+
+    fn get_objects(&self, layer: &str) -> Result<&[TiledObject], String> {
+        self.find_layer(layer)
+            .map(|layer| layer.objects.as_slice())
+            .ok_or_else(|| format!("Tiled map has no layer {}", layer))
+    }
+
+    /// Imports every polyline object of `layer` as a path named like the object.
+    pub fn import_paths(&self, layer: &str) -> Result<PathDefinitions, String> {
+        let mut paths = PathDefinitions::new();
+        for object in self.get_objects(layer)? {
+            if let Some(polyline) = &object.polyline {
+                let points = polyline
+                    .iter()
+                    .map(|point| Vector2::new(object.x + point.x, object.y + point.y))
+                    .collect();
+                paths.insert(object.name.clone(), PathDefinition::from_points(points)?);
+            }
+        }
+
+        Ok(paths)
+    }
+
+    /// Imports all rectangle objects called `name` of `layer`.
+    pub fn import_rects(&self, layer: &str, name: &str) -> Result<Vec<RectDefinition>, String> {
+        let mut rects = vec![];
+        for object in self.get_objects(layer)? {
+            if object.name != name || object.polyline.is_some() {
+                continue;
+            }
+            if object.rotation != 0.0 {
+                return Err(format!("Tiled object {} must not be rotated", name));
+            }
+
+            rects.push(RectDefinition::new(
+                Vector2::new(object.x, object.y),
+                Vector2::new(object.x, object.y + object.height),
+                Vector2::new(object.x + object.width, object.y + object.height),
+            ));
+        }
+
+        Ok(rects)
+    }
+}
+
+#[cfg(test)]
+mod tiled_tests {
+    use crate::map::tiled::TiledMap;
+    use crate::math::Vector2;
+
+    #[test]
+    fn import_objects() {
+        let tiled: TiledMap = serde_json::from_str(
+            r#"{
+                "layers": [
+                    { "name": "ground", "type": "tilelayer", "data": [] },
+                    { "name": "level", "type": "group", "layers": [
+                        { "name": "objects", "type": "objectgroup", "objects": [
+                            { "name": "path", "x": 10, "y": 20,
+                              "polyline": [{ "x": 0, "y": 0 }, { "x": 100, "y": 0 }, { "x": 100, "y": 50 }] },
+                            { "name": "base", "x": 100, "y": 60, "width": 20, "height": 10 }
+                        ] }
+                    ] }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let paths = tiled.import_paths("objects").unwrap();
+        assert_eq!(paths.keys().collect::<Vec<_>>(), vec!["path"]);
+        let path = paths["path"].build().unwrap();
+        assert_eq!(path.length(), 150.0);
+        assert!((&path.coords_at(150.0) - &Vector2::new(110.0, 70.0)).magnitude() < 1e-9);
+
+        let bases = tiled.import_rects("objects", "base").unwrap();
+        assert_eq!(bases.len(), 1);
+        assert!(bases[0].build().is_inside(&Vector2::new(110.0, 65.0)));
+        assert!(tiled.import_rects("objects", "missing").unwrap().is_empty());
+        assert!(tiled.import_paths("missing").is_err());
+    }
+}
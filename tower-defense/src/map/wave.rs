@@ -1,30 +1,502 @@
 use crate::entity::EnemyType;
-use log::info;
+use crate::tick::{self, Tick};
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-pub struct Wave {
+/****************************************
+* Wave Definitions
+*****************************************/
+
+/// A group of enemies of the same type spawned one after another.
+/// All durations are in milliseconds.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EnemyGroup {
+    enemy: EnemyType,
+    count: u32,
+    /// Time between two spawns of this group.
+    spacing: f64,
+    /// Time from the start of the wave until the first enemy of this group spawns.
+    #[serde(default)]
+    start_delay: f64,
+    /// The path the enemies walk along, the default path of the map if not set.
+    #[serde(default)]
+    path: Option<String>,
+}
+
+impl EnemyGroup {
+    pub fn new(enemy: EnemyType, count: u32, spacing: f64, start_delay: f64) -> Self {
+        Self {
+            enemy,
+            count,
+            spacing,
+            start_delay,
+            path: None,
+        }
+    }
+
+    pub fn on_path(self, path: String) -> Self {
+        Self {
+            path: Some(path),
+            ..self
+        }
+    }
+
+    /// Time from the start of the wave until the last enemy of this group spawns.
+    fn last_spawn(&self) -> f64 {
+        self.start_delay + self.spacing.max(0.0) * self.count.saturating_sub(1) as f64
+    }
+
+    /// Number of enemies of this group that should have spawned `elapsed` ms into the wave.
+    fn due(&self, elapsed: f64) -> u32 {
+        if elapsed < self.start_delay {
+            return 0;
+        }
+        if self.spacing <= 0.0 {
+            return self.count;
+        }
+        let due = ((elapsed - self.start_delay) / self.spacing).floor() as u32 + 1;
+        due.min(self.count)
+    }
+}
+
+/// An enemy, usually a boss, that spawns after every group of its wave has spawned.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BossEntry {
+    enemy: EnemyType,
+    /// Time between the last spawn of the groups and the boss.
+    #[serde(default)]
+    delay: f64,
+    #[serde(default)]
+    path: Option<String>,
+}
+
+impl BossEntry {
+    pub fn new(enemy: EnemyType, delay: f64) -> Self {
+        Self {
+            enemy,
+            delay,
+            path: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WaveDefinition {
+    groups: Vec<EnemyGroup>,
+    #[serde(default)]
+    boss: Option<BossEntry>,
+}
+
+impl WaveDefinition {
+    pub fn new(groups: Vec<EnemyGroup>) -> Self {
+        Self { groups, boss: None }
+    }
+
+    pub fn with_boss(self, boss: BossEntry) -> Self {
+        Self {
+            boss: Some(boss),
+            ..self
+        }
+    }
+
+    /// The groups of the wave, the boss being a group of its own after the others.
+    fn get_groups(&self) -> Vec<EnemyGroup> {
+        let mut groups = self.groups.clone();
+        if let Some(boss) = &self.boss {
+            let end = groups
+                .iter()
+                .map(EnemyGroup::last_spawn)
+                .fold(0.0, f64::max);
+            let mut group = EnemyGroup::new(boss.enemy.clone(), 1, 0.0, end + boss.delay);
+            group.path = boss.path.clone();
+            groups.push(group);
+        }
+
+        groups
+    }
+}
+
+/// Randomly generated waves that follow once all authored waves have been cleared.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EndlessWaves {
+    /// The enemies endless waves are drawn from, each with the same probability.
+    enemies: Vec<EnemyType>,
+    /// The paths endless enemies are spread over, only the default path if empty.
+    #[serde(default)]
+    paths: Vec<String>,
     min_respawn_duration: f64,
     max_respawn_duration: f64,
-    next_respawn: f64,
+    base_count: u32,
+    count_increase: u32,
 }
 
-impl Wave {
-    pub fn new(min_respawn_duration: f64, max_respawn_duration: f64) -> Self {
-        Wave {
+impl EndlessWaves {
+    pub fn new(
+        enemies: Vec<EnemyType>,
+        min_respawn_duration: f64,
+        max_respawn_duration: f64,
+        base_count: u32,
+        count_increase: u32,
+    ) -> Self {
+        Self {
+            enemies,
+            paths: vec![],
             min_respawn_duration,
             max_respawn_duration,
-            next_respawn: 0.0,
+            base_count,
+            count_increase,
+        }
+    }
+
+    /// Checks the values that would make generating a wave panic.
+    fn validate(&self) -> Result<(), String> {
+        if self.min_respawn_duration >= self.max_respawn_duration {
+            return Err(format!(
+                "The min_respawn_duration {} of endless waves has to be less than the \
+                 max_respawn_duration {}",
+                self.min_respawn_duration, self.max_respawn_duration
+            ));
         }
+
+        Ok(())
+    }
+
+    /// Generates the `n`th endless wave (starting at 0) as single enemy groups at random intervals.
+    fn generate<R: Rng>(&self, n: u32, rng: &mut R) -> Vec<EnemyGroup> {
+        if self.enemies.is_empty() {
+            return vec![];
+        }
+        let count = self.base_count + self.count_increase * n;
+        let mut start_delay = 0.0;
+        let mut groups = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let enemy = self.enemies[rng.gen_range(0..self.enemies.len())].clone();
+            let mut group = EnemyGroup::new(enemy, 1, 0.0, start_delay);
+            if !self.paths.is_empty() {
+                let path = self.paths[rng.gen_range(0..self.paths.len())].clone();
+                group = group.on_path(path);
+            }
+            groups.push(group);
+            start_delay += rng.gen_range(self.min_respawn_duration..self.max_respawn_duration);
+        }
+
+        groups
     }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WaveSchedule {
+    /// Time before the first wave starts.
+    initial_delay: f64,
+    /// Time between a wave being cleared and the next one starting.
+    pause_between_waves: f64,
+    waves: Vec<WaveDefinition>,
+    #[serde(default)]
+    endless: Option<EndlessWaves>,
+}
 
-    pub fn update(&mut self, delta_time: f64) -> Option<EnemyType> {
-        self.next_respawn -= delta_time;
-        if self.next_respawn < 0.0 {
-            self.next_respawn =
-                rand::thread_rng().gen_range(self.min_respawn_duration..self.max_respawn_duration);
-            return Some(EnemyType::random());
+impl WaveSchedule {
+    pub fn new(
+        initial_delay: f64,
+        pause_between_waves: f64,
+        waves: Vec<WaveDefinition>,
+        endless: Option<EndlessWaves>,
+    ) -> Self {
+        Self {
+            initial_delay,
+            pause_between_waves,
+            waves,
+            endless,
         }
+    }
+
+    pub fn get_wave_count(&self) -> usize {
+        self.waves.len()
+    }
+
+    /// Names of all paths the waves refer to.
+    pub fn get_path_names(&self) -> impl Iterator<Item = &str> {
+        let groups = self.waves.iter().flat_map(|wave| wave.groups.iter());
+        let bosses = self.waves.iter().filter_map(|wave| wave.boss.as_ref());
+        let endless = self.endless.iter().flat_map(|endless| endless.paths.iter());
+        groups
+            .filter_map(|group| group.path.as_deref())
+            .chain(bosses.filter_map(|boss| boss.path.as_deref()))
+            .chain(endless.map(|path| path.as_str()))
+    }
+
+    /// Checks that waves can be generated from the schedule.
+    pub fn validate(&self) -> Result<(), String> {
+        match &self.endless {
+            Some(endless) => endless.validate(),
+            None => Ok(()),
+        }
+    }
+
+    fn get_groups<R: Rng>(&self, number: usize, rng: &mut R) -> Option<Vec<EnemyGroup>> {
+        match self.waves.get(number - 1) {
+            Some(wave) => Some(wave.get_groups()),
+            None => self
+                .endless
+                .as_ref()
+                .map(|endless| endless.generate((number - self.waves.len() - 1) as u32, rng)),
+        }
+    }
+}
+
+/****************************************
+* Wave Progress
+*****************************************/
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(tag = "type", content = "data")]
+pub enum WaveState {
+    Waiting { next_wave_start: Tick },
+    Spawning { wave_start: Tick },
+    Clearing,
+    Finished,
+}
+
+/// Keeps track of which wave of a [`WaveSchedule`] is currently running.
+#[derive(Serialize, Deserialize)]
+pub struct WaveProgress {
+    /// The current wave number starting at 1. 0 while waiting for the first wave.
+    wave: usize,
+    wave_count: usize,
+    state: WaveState,
+    spawned: u32,
+    total: u32,
+    groups: Vec<EnemyGroup>,
+    group_spawned: Vec<u32>,
+}
+
+impl WaveProgress {
+    pub fn new(schedule: &WaveSchedule) -> Self {
+        Self {
+            wave: 0,
+            wave_count: schedule.get_wave_count(),
+            state: WaveState::Waiting {
+                next_wave_start: tick::from_millis(schedule.initial_delay),
+            },
+            spawned: 0,
+            total: 0,
+            groups: vec![],
+            group_spawned: vec![],
+        }
+    }
+
+    pub fn get_wave(&self) -> usize {
+        self.wave
+    }
+
+    pub fn get_state(&self) -> &WaveState {
+        &self.state
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.state == WaveState::Finished
+    }
+
+    /// Advances the schedule and returns the enemies that have to be spawned together with the
+    /// path they take, `None` being the default path of the map.
+    /// `field_clear` signals that no enemies are left on the map, which ends the current wave.
+    pub fn update<R: Rng>(
+        &mut self,
+        schedule: &WaveSchedule,
+        tick: Tick,
+        field_clear: bool,
+        rng: &mut R,
+    ) -> Vec<(EnemyType, Option<String>)> {
+        match self.state {
+            WaveState::Waiting { next_wave_start } if next_wave_start <= tick => {
+                self.start_wave(schedule, next_wave_start, rng);
+                self.spawn(tick)
+            }
+            WaveState::Spawning { .. } => self.spawn(tick),
+            WaveState::Clearing if field_clear => {
+                self.state = WaveState::Waiting {
+                    next_wave_start: tick + tick::from_millis(schedule.pause_between_waves),
+                };
+                vec![]
+            }
+            _ => vec![],
+        }
+    }
+
+    fn start_wave<R: Rng>(&mut self, schedule: &WaveSchedule, wave_start: Tick, rng: &mut R) {
+        match schedule.get_groups(self.wave + 1, rng) {
+            Some(groups) => {
+                self.wave += 1;
+                self.spawned = 0;
+                self.total = groups.iter().map(|group| group.count).sum();
+                self.group_spawned = vec![0; groups.len()];
+                self.groups = groups;
+                self.state = WaveState::Spawning { wave_start };
+            }
+            None => self.state = WaveState::Finished,
+        }
+    }
+
+    fn spawn(&mut self, tick: Tick) -> Vec<(EnemyType, Option<String>)> {
+        let wave_start = match self.state {
+            WaveState::Spawning { wave_start } => wave_start,
+            _ => return vec![],
+        };
+
+        let mut enemies = vec![];
+        for (group, spawned) in self.groups.iter().zip(self.group_spawned.iter_mut()) {
+            let due = group.due(tick::to_millis(tick - wave_start));
+            for _ in *spawned..due {
+                enemies.push((group.enemy.clone(), group.path.clone()));
+            }
+            self.spawned += due - *spawned;
+            *spawned = due;
+        }
+
+        if self.spawned >= self.total {
+            self.state = WaveState::Clearing;
+        }
+
+        enemies
+    }
+}
+
+#[cfg(test)]
+mod wave_tests {
+    use crate::entity::EnemyType;
+    use crate::map::{
+        BossEntry, EndlessWaves, EnemyGroup, WaveDefinition, WaveProgress, WaveSchedule, WaveState,
+    };
+    use crate::tick;
+    use rand::SeedableRng;
+    use rand_pcg::Pcg32;
+
+    #[test]
+    fn spawns_groups_in_order() {
+        let schedule = WaveSchedule::new(
+            1000.0,
+            5000.0,
+            vec![
+                WaveDefinition::new(vec![
+                    EnemyGroup::new(EnemyType::new_unchecked("Blue"), 3, 500.0, 0.0),
+                    EnemyGroup::new(EnemyType::new_unchecked("Red"), 1, 0.0, 200.0),
+                ]),
+                WaveDefinition::new(vec![EnemyGroup::new(
+                    EnemyType::new_unchecked("Purple"),
+                    1,
+                    0.0,
+                    0.0,
+                )]),
+            ],
+            None,
+        );
+        let mut progress = WaveProgress::new(&schedule);
+        let rng = &mut Pcg32::seed_from_u64(0);
+
+        assert!(progress
+            .update(&schedule, tick::from_millis(980.0), true, rng)
+            .is_empty());
+        assert_eq!(
+            progress
+                .update(&schedule, tick::from_millis(1000.0), true, rng)
+                .len(),
+            1
+        );
+        assert_eq!(progress.get_wave(), 1);
+        assert_eq!(
+            progress
+                .update(&schedule, tick::from_millis(1200.0), true, rng)
+                .len(),
+            1
+        );
+        assert_eq!(
+            progress
+                .update(&schedule, tick::from_millis(2000.0), true, rng)
+                .len(),
+            2
+        );
+        assert_eq!(progress.get_state(), &WaveState::Clearing);
+
+        // The next wave only starts once the field has been cleared and the pause is over.
+        assert!(progress
+            .update(&schedule, tick::from_millis(3000.0), false, rng)
+            .is_empty());
+        assert!(progress
+            .update(&schedule, tick::from_millis(4000.0), true, rng)
+            .is_empty());
+        assert!(progress
+            .update(&schedule, tick::from_millis(8980.0), true, rng)
+            .is_empty());
+        assert_eq!(
+            progress
+                .update(&schedule, tick::from_millis(9000.0), true, rng)
+                .len(),
+            1
+        );
+        assert_eq!(progress.get_wave(), 2);
+
+        progress.update(&schedule, tick::from_millis(9100.0), true, rng);
+        progress.update(&schedule, tick::from_millis(15000.0), true, rng);
+        assert!(progress.is_finished());
+    }
+
+    #[test]
+    fn boss_spawns_last() {
+        let blue = EnemyType::new_unchecked("Blue");
+        let boss = EnemyType::new_unchecked("PurpleKing");
+        let schedule = WaveSchedule::new(
+            0.0,
+            0.0,
+            vec![
+                WaveDefinition::new(vec![EnemyGroup::new(blue, 3, 500.0, 200.0)])
+                    .with_boss(BossEntry::new(boss.clone(), 1000.0)),
+            ],
+            None,
+        );
+        let mut progress = WaveProgress::new(&schedule);
+        let rng = &mut Pcg32::seed_from_u64(0);
+
+        // The last Blue spawns after 1200 ms, the boss a second later.
+        progress.update(&schedule, 0, true, rng);
+        progress.update(&schedule, tick::from_millis(2180.0), false, rng);
+        assert_eq!(progress.get_state(), &WaveState::Spawning { wave_start: 0 });
+        let spawns = progress.update(&schedule, tick::from_millis(2200.0), false, rng);
+        assert_eq!(spawns, vec![(boss, None)]);
+        assert_eq!(progress.get_state(), &WaveState::Clearing);
+    }
+
+    #[test]
+    fn endless_waves_are_reproducible() {
+        let schedule = WaveSchedule::new(
+            0.0,
+            0.0,
+            vec![],
+            Some(EndlessWaves::new(
+                vec![EnemyType::new_unchecked("Blue")],
+                300.0,
+                1500.0,
+                20,
+                0,
+            )),
+        );
+        let spawn_times = |seed: u64| {
+            let rng = &mut Pcg32::seed_from_u64(seed);
+            let mut progress = WaveProgress::new(&schedule);
+            let mut spawn_times = vec![];
+            for tick in 0..tick::from_millis(60_000.0) {
+                if !progress.update(&schedule, tick, true, rng).is_empty() {
+                    spawn_times.push(tick);
+                }
+            }
+            spawn_times
+        };
+
+        assert_eq!(spawn_times(42), spawn_times(42));
+        assert_ne!(spawn_times(42), spawn_times(43));
 
-        None
+        let empty_range =
+            EndlessWaves::new(vec![EnemyType::new_unchecked("Blue")], 500.0, 500.0, 20, 0);
+        let schedule = WaveSchedule::new(0.0, 0.0, vec![], Some(empty_range));
+        assert!(schedule.validate().is_err());
     }
 }
@@ -1,5 +1,9 @@
+mod polygon;
 mod rect;
+mod spatial_index;
 mod vector2;
 
+pub use polygon::{distance_to_segment, project_onto_segment, Polygon};
 pub use rect::Rect;
-pub use vector2::Vector2;
\ No newline at end of file
+pub use spatial_index::SpatialIndex;
+pub use vector2::Vector2;
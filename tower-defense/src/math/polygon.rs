@@ -0,0 +1,111 @@
+use crate::math::vector2::Vector2;
+use serde::{Deserialize, Serialize};
+
+/// A closed polygon given by its corners in order.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(transparent)]
+pub struct Polygon {
+    points: Vec<Vector2>,
+}
+
+impl Polygon {
+    pub fn new(points: Vec<Vector2>) -> Self {
+        Self { points }
+    }
+
+    pub fn get_points(&self) -> &[Vector2] {
+        &self.points
+    }
+
+    /// Counts how often a ray from `point` crosses the edges of the polygon.
+    pub fn contains(&self, point: &Vector2) -> bool {
+        let mut inside = false;
+        let n = self.points.len();
+        for i in 0..n {
+            let a = &self.points[i];
+            let b = &self.points[(i + n - 1) % n];
+            if (a.y() > point.y()) != (b.y() > point.y()) {
+                let x = a.x() + (point.y() - a.y()) / (b.y() - a.y()) * (b.x() - a.x());
+                if point.x() < x {
+                    inside = !inside;
+                }
+            }
+        }
+
+        inside
+    }
+
+    /// The shortest distance from `point` to the outline of the polygon.
+    pub fn distance_to_outline(&self, point: &Vector2) -> f64 {
+        let n = self.points.len();
+        (0..n)
+            .map(|i| distance_to_segment(point, &self.points[i], &self.points[(i + 1) % n]))
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// Whether the circle around `center` lies completely inside the polygon.
+    pub fn contains_circle(&self, center: &Vector2, radius: f64) -> bool {
+        self.contains(center) && self.distance_to_outline(center) >= radius
+    }
+
+    /// Whether the circle around `center` and the polygon overlap.
+    pub fn overlaps_circle(&self, center: &Vector2, radius: f64) -> bool {
+        self.contains(center) || self.distance_to_outline(center) < radius
+    }
+}
+
+/// The shortest distance from `point` to the line segment between `a` and `b`.
+pub fn distance_to_segment(point: &Vector2, a: &Vector2, b: &Vector2) -> f64 {
+    let t = project_onto_segment(point, a, b);
+    (point - &(a + &(&(b - a) * t))).magnitude()
+}
+
+/// Where the point of the segment from `a` to `b` that is closest to `point` lies, 0 being `a`
+/// and 1 being `b`.
+pub fn project_onto_segment(point: &Vector2, a: &Vector2, b: &Vector2) -> f64 {
+    let ab = b - a;
+    let length_squared = ab.dot(&ab);
+    if length_squared == 0.0 {
+        return 0.0;
+    }
+    ((point - a).dot(&ab) / length_squared).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod polygon_tests {
+    use crate::math::{distance_to_segment, Polygon, Vector2};
+
+    #[test]
+    fn contains() {
+        // An L shape.
+        let polygon = Polygon::new(vec![
+            Vector2::new(0.0, 0.0),
+            Vector2::new(20.0, 0.0),
+            Vector2::new(20.0, 10.0),
+            Vector2::new(10.0, 10.0),
+            Vector2::new(10.0, 20.0),
+            Vector2::new(0.0, 20.0),
+        ]);
+
+        assert!(polygon.contains(&Vector2::new(5.0, 5.0)));
+        assert!(polygon.contains(&Vector2::new(15.0, 5.0)));
+        assert!(polygon.contains(&Vector2::new(5.0, 15.0)));
+        assert!(!polygon.contains(&Vector2::new(15.0, 15.0)));
+        assert!(!polygon.contains(&Vector2::new(-1.0, 5.0)));
+
+        assert!(polygon.contains_circle(&Vector2::new(5.0, 15.0), 4.0));
+        assert!(!polygon.contains_circle(&Vector2::new(5.0, 15.0), 6.0));
+        assert!(polygon.overlaps_circle(&Vector2::new(15.0, 15.0), 6.0));
+        assert!(!polygon.overlaps_circle(&Vector2::new(15.0, 15.0), 4.0));
+    }
+
+    #[test]
+    fn segment_distance() {
+        let a = Vector2::new(0.0, 0.0);
+        let b = Vector2::new(10.0, 0.0);
+
+        assert_eq!(distance_to_segment(&Vector2::new(5.0, 3.0), &a, &b), 3.0);
+        assert_eq!(distance_to_segment(&Vector2::new(13.0, 4.0), &a, &b), 5.0);
+        assert_eq!(distance_to_segment(&Vector2::new(-3.0, 0.0), &a, &a), 3.0);
+    }
+}
@@ -0,0 +1,193 @@
+use crate::math::vector2::Vector2;
+
+/// A uniform grid over a rectangular area that finds points close to a position without looking
+/// at every point. Points are referred to by the order they were inserted in, so that the index
+/// can be used alongside the slice it was built from.
+///
+/// Points outside of the area are kept in the closest cell at the border and are still found.
+pub struct SpatialIndex {
+    cell_size: f64,
+    columns: usize,
+    rows: usize,
+    /// Start of every cell in `entries`, followed by the end of the last cell.
+    cell_starts: Vec<usize>,
+    /// Indices of the points sorted by cell.
+    entries: Vec<usize>,
+    points: Vec<Vector2>,
+}
+
+impl SpatialIndex {
+    pub fn new(width: f64, height: f64, cell_size: f64) -> Self {
+        let columns = ((width / cell_size).ceil() as usize).max(1);
+        let rows = ((height / cell_size).ceil() as usize).max(1);
+        Self {
+            cell_size,
+            columns,
+            rows,
+            cell_starts: vec![0; columns * rows + 1],
+            entries: vec![],
+            points: vec![],
+        }
+    }
+
+    /// Replaces all points of the index.
+    pub fn rebuild<'a>(&mut self, points: impl IntoIterator<Item = &'a Vector2>) {
+        self.points.clear();
+        self.points.extend(points.into_iter().cloned());
+
+        // Counting sort of the points by cell, so that the points of a cell are next to each
+        // other without allocating per cell.
+        self.cell_starts.iter_mut().for_each(|start| *start = 0);
+        for i in 0..self.points.len() {
+            let cell = self.cell_index(&self.points[i]);
+            self.cell_starts[cell + 1] += 1;
+        }
+        for i in 1..self.cell_starts.len() {
+            self.cell_starts[i] += self.cell_starts[i - 1];
+        }
+        let mut next = self.cell_starts.clone();
+        self.entries.clear();
+        self.entries.resize(self.points.len(), 0);
+        for (i, point) in self.points.iter().enumerate() {
+            let cell = self.cell_index(point);
+            self.entries[next[cell]] = i;
+            next[cell] += 1;
+        }
+    }
+
+    /// The points in the given cell, in the order they were inserted in.
+    fn cell(&self, column: usize, row: usize) -> &[usize] {
+        let cell = row * self.columns + column;
+        &self.entries[self.cell_starts[cell]..self.cell_starts[cell + 1]]
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// The points closer than `radius` to `center`, in the order they were inserted in.
+    pub fn within(&self, center: &Vector2, radius: f64) -> Vec<usize> {
+        let (min_column, min_row) =
+            self.cell_of(&Vector2::new(center.x() - radius, center.y() - radius));
+        let (max_column, max_row) =
+            self.cell_of(&Vector2::new(center.x() + radius, center.y() + radius));
+
+        let mut found = vec![];
+        for row in min_row..=max_row {
+            for column in min_column..=max_column {
+                for &i in self.cell(column, row) {
+                    if (&self.points[i] - center).magnitude() < radius {
+                        found.push(i);
+                    }
+                }
+            }
+        }
+        found.sort_unstable();
+
+        found
+    }
+
+    /// The point closest to `center` that is closer than `max_distance` and accepted by
+    /// `filter`. Ties go to the point inserted first.
+    pub fn nearest(
+        &self,
+        center: &Vector2,
+        max_distance: f64,
+        filter: impl Fn(usize) -> bool,
+    ) -> Option<usize> {
+        let (center_column, center_row) = self.cell_of(center);
+        let mut best: Option<(f64, usize)> = None;
+
+        // Searches rings of cells around the cell of the center. Points in ring `k + 1` are at
+        // least `k` cells away, so the search can stop once the best point is closer than that.
+        for k in 0..self.columns.max(self.rows) {
+            let min_distance = k.saturating_sub(1) as f64 * self.cell_size;
+            if min_distance >= max_distance || best.is_some_and(|(d, _)| d < min_distance) {
+                break;
+            }
+
+            for (column, row) in self.ring(center_column, center_row, k) {
+                for &i in self.cell(column, row) {
+                    let distance = (&self.points[i] - center).magnitude();
+                    let is_better = match best {
+                        Some((d, j)) => distance < d || (distance == d && i < j),
+                        None => true,
+                    };
+                    if distance < max_distance && is_better && filter(i) {
+                        best = Some((distance, i));
+                    }
+                }
+            }
+        }
+
+        best.map(|(_, i)| i)
+    }
+
+    /// The cells exactly `k` cells away from the given cell, horizontally or vertically.
+    fn ring(&self, column: usize, row: usize, k: usize) -> Vec<(usize, usize)> {
+        let (column, row, k) = (column as i64, row as i64, k as i64);
+        let mut cells = vec![];
+        for r in (row - k)..=(row + k) {
+            for c in (column - k)..=(column + k) {
+                let on_ring = (r - row).abs() == k || (c - column).abs() == k;
+                if on_ring && r >= 0 && c >= 0 && r < self.rows as i64 && c < self.columns as i64 {
+                    cells.push((c as usize, r as usize));
+                }
+            }
+        }
+
+        cells
+    }
+
+    fn cell_index(&self, point: &Vector2) -> usize {
+        let (column, row) = self.cell_of(point);
+        row * self.columns + column
+    }
+
+    fn cell_of(&self, point: &Vector2) -> (usize, usize) {
+        let clamp = |value: f64, cells: usize| {
+            ((value / self.cell_size).floor().max(0.0) as usize).min(cells - 1)
+        };
+        (clamp(point.x(), self.columns), clamp(point.y(), self.rows))
+    }
+}
+
+#[cfg(test)]
+mod spatial_index_tests {
+    use crate::math::{SpatialIndex, Vector2};
+
+    #[test]
+    fn queries() {
+        let points: Vec<Vector2> = (0..400)
+            .map(|i| Vector2::new((i * 37 % 1000) as f64, (i * 91 % 600) as f64 - 20.0))
+            .collect();
+        let mut index = SpatialIndex::new(1000.0, 600.0, 64.0);
+        index.rebuild(&points);
+
+        for center in [
+            Vector2::new(500.0, 300.0),
+            Vector2::new(3.0, 590.0),
+            Vector2::new(-50.0, 700.0),
+        ] {
+            let expected: Vec<usize> = (0..points.len())
+                .filter(|&i| (&points[i] - &center).magnitude() < 150.0)
+                .collect();
+            assert_eq!(index.within(&center, 150.0), expected);
+
+            let expected = (0..points.len()).filter(|i| i % 2 == 0).min_by(|&a, &b| {
+                let a = (&points[a] - &center).magnitude();
+                let b = (&points[b] - &center).magnitude();
+                a.total_cmp(&b)
+            });
+            assert_eq!(
+                index.nearest(&center, f64::INFINITY, |i| i % 2 == 0),
+                expected
+            );
+        }
+        assert_eq!(index.nearest(&Vector2::new(0.0, 0.0), 0.5, |_| true), None);
+    }
+}
@@ -40,6 +40,17 @@ impl<'a> ops::Add for &'a Vector2 {
     }
 }
 
+impl ops::Mul<f64> for &Vector2 {
+    type Output = Vector2;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        Vector2 {
+            x: self.x * rhs,
+            y: self.y * rhs,
+        }
+    }
+}
+
 impl<'a> ops::Sub for &'a Vector2 {
     type Output = Vector2;
 
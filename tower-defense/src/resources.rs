@@ -0,0 +1,90 @@
+use crate::entity::GifFrames;
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::fmt::Formatter;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+/// Directory containing all game data, relative to the working directory of the server.
+#[cfg(not(test))]
+pub const RESOURCES_DIR: &str = "resources";
+
+/// Tests run in the directory of this crate and use the resources of the server.
+#[cfg(test)]
+pub const RESOURCES_DIR: &str = "../tower-defense-server/resources";
+
+/// Sub directory of [`RESOURCES_DIR`] that is served to clients. Sprite and frame paths in
+/// definition files are relative to it.
+const WWW_DIR: &str = "www";
+
+#[derive(Debug, Clone)]
+pub struct ResourceError {
+    path: PathBuf,
+    message: String,
+}
+
+impl ResourceError {
+    pub fn new(path: &Path, message: String) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for ResourceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.message)
+    }
+}
+
+impl std::error::Error for ResourceError {}
+
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, ResourceError> {
+    let file = File::open(path).map_err(|e| ResourceError::new(path, e.to_string()))?;
+    let reader = BufReader::new(file);
+    serde_json::from_reader(reader).map_err(|e| ResourceError::new(path, e.to_string()))
+}
+
+/// Lists every `.json` file in `dir`, sorted by file name so that the result does not depend on
+/// the order of the file system.
+pub fn list_json_files(dir: &Path) -> Result<Vec<PathBuf>, ResourceError> {
+    let entries = dir
+        .read_dir()
+        .map_err(|e| ResourceError::new(dir, e.to_string()))?;
+    let mut paths = vec![];
+    for entry in entries {
+        let path = entry
+            .map_err(|e| ResourceError::new(dir, e.to_string()))?
+            .path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    Ok(paths)
+}
+
+/// Reads every `.json` file in `dir`, see [`list_json_files`].
+pub fn read_json_dir<T: DeserializeOwned>(dir: &Path) -> Result<Vec<(String, T)>, ResourceError> {
+    let mut result = vec![];
+    for path in list_json_files(dir)? {
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        result.push((name, read_json(&path)?));
+    }
+
+    Ok(result)
+}
+
+/// Loads the frame description of a sprite sheet. `path` is relative to the served directory.
+pub fn load_gif_frames(resources: &Path, path: &str) -> Result<GifFrames, ResourceError> {
+    read_json(&resources.join(WWW_DIR).join(path))
+}
@@ -0,0 +1,36 @@
+/// The simulation advances in fixed steps. All game time is counted in ticks, while designer
+/// facing durations (cooldowns, spawn intervals, ...) stay in milliseconds and are converted.
+pub type Tick = u64;
+
+pub const TICKS_PER_SECOND: u64 = 50;
+
+/// Duration of a single tick in milliseconds.
+pub const TICK_DURATION: u64 = 1000 / TICKS_PER_SECOND;
+
+/// Duration of a single tick in seconds.
+pub const DELTA_TIME: f64 = TICK_DURATION as f64 / 1000.0;
+
+/// Converts a duration in milliseconds to the number of ticks it spans, rounding up so that a
+/// non-zero duration never completes instantly.
+pub fn from_millis(ms: f64) -> Tick {
+    (ms / TICK_DURATION as f64).ceil() as Tick
+}
+
+/// Converts a number of ticks to milliseconds.
+pub fn to_millis(ticks: Tick) -> f64 {
+    (ticks * TICK_DURATION) as f64
+}
+
+#[cfg(test)]
+mod tick_tests {
+    use crate::tick::{from_millis, to_millis, TICK_DURATION};
+
+    #[test]
+    fn conversion() {
+        assert_eq!(from_millis(0.0), 0);
+        assert_eq!(from_millis(1.0), 1);
+        assert_eq!(from_millis(TICK_DURATION as f64), 1);
+        assert_eq!(from_millis(TICK_DURATION as f64 + 1.0), 2);
+        assert_eq!(to_millis(from_millis(1000.0)), 1000.0);
+    }
+}